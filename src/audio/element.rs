@@ -0,0 +1,104 @@
+use web_sys::HtmlAudioElement;
+use js_sys::Promise;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen::JsValue;
+use std::time::Duration;
+
+/// The HtmlAudioElement glue shared by [Sound](../sound/struct.Sound.html) and [Music](../music/struct.Music.html).
+#[derive(Debug, PartialEq)]
+pub(crate) struct AudioElement {
+    element: HtmlAudioElement,
+}
+
+impl AudioElement {
+    /// Load an audio element and wait until `ready_event` ("canplay" or "canplaythrough") is fired.
+    pub(crate) async fn load(url: &str, ready_event: &str) -> Result<AudioElement, JsValue> {
+        let element = HtmlAudioElement::new()?;
+        element.set_preload("auto");
+
+        let promise = Promise::new(&mut |yes, no| {
+            element.add_event_listener_with_callback(ready_event, &yes).unwrap();
+            element.add_event_listener_with_callback("error", &no).unwrap();
+        });
+        element.set_src(url);
+        JsFuture::from(promise).await?;
+
+        Ok(AudioElement {
+            element
+        })
+    }
+
+    pub(crate) fn get_html_element(&self) -> &HtmlAudioElement {
+        &self.element
+    }
+
+    pub(crate) fn play(&mut self) {
+        // the returned promise is rejected if autoplay is blocked, there is nothing we can do about it
+        let _ = self.element.play();
+    }
+
+    pub(crate) fn pause(&mut self) {
+        self.element.pause().unwrap();
+    }
+
+    pub(crate) fn stop(&mut self) {
+        self.element.pause().unwrap();
+        self.element.set_current_time(0.0);
+    }
+
+    pub(crate) fn is_playing(&self) -> bool {
+        !self.element.paused() && !self.element.ended()
+    }
+
+    pub(crate) fn set_loop(&mut self, looping: bool) {
+        self.element.set_loop(looping);
+    }
+
+    pub(crate) fn is_looping(&self) -> bool {
+        self.element.loop_()
+    }
+
+    pub(crate) fn set_volume(&mut self, volume: f64) {
+        self.element.set_volume(volume.clamp(0.0, 1.0));
+    }
+
+    pub(crate) fn get_volume(&self) -> f64 {
+        self.element.volume()
+    }
+
+    pub(crate) fn set_current_time(&mut self, offset: Duration) {
+        self.element.set_current_time(offset.as_secs_f64());
+    }
+
+    pub(crate) fn get_current_time(&self) -> Duration {
+        secs_to_duration(self.element.current_time()).unwrap_or_default()
+    }
+
+    pub(crate) fn get_duration(&self) -> Option<Duration> {
+        secs_to_duration(self.element.duration())
+    }
+}
+
+/// Convert a media time to a Duration.
+/// Media elements report NaN while the metadata is not loaded and infinity for streams.
+fn secs_to_duration(secs: f64) -> Option<Duration> {
+    if secs.is_finite() && secs >= 0.0 {
+        Some(Duration::from_secs_f64(secs))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secs_to_duration() {
+        assert_eq!(secs_to_duration(1.5), Some(Duration::from_millis(1500)));
+        assert_eq!(secs_to_duration(0.0), Some(Duration::from_secs(0)));
+        assert_eq!(secs_to_duration(f64::NAN), None);
+        assert_eq!(secs_to_duration(f64::INFINITY), None);
+        assert_eq!(secs_to_duration(-1.0), None);
+    }
+}
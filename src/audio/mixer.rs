@@ -0,0 +1,336 @@
+use super::sound::Sound;
+use super::music::Music;
+use super::positional::Emitter;
+use web_sys::{AudioContext, AudioNode, AudioParam, GainNode, HtmlMediaElement};
+use wasm_bindgen::JsValue;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The names of the buses created by [Mixer::new()](struct.Mixer.html#method.new).
+pub const DEFAULT_BUSES: [&str; 3] = ["sfx", "music", "ui"];
+
+/// A named group of sounds sharing the same gain.
+/// Buses are created and modified through the [Mixer](struct.Mixer.html).
+#[derive(Debug)]
+pub struct Bus {
+    node: GainNode,
+    levels: Levels,
+}
+
+/// The settings of a bus which affect its gain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Levels {
+    volume: f64,
+    muted: bool,
+    solo: bool,
+    ducking: f64,
+}
+
+impl Default for Levels {
+    fn default() -> Levels {
+        Levels {
+            volume: 1.0,
+            muted: false,
+            solo: false,
+            ducking: 1.0,
+        }
+    }
+}
+
+impl Levels {
+    /// Return the gain actually applied to the bus.
+    /// `any_solo` must be true if at least one bus of the mixer is soloed.
+    fn gain(&self, any_solo: bool) -> f64 {
+        if self.muted || (any_solo && !self.solo) {
+            0.0
+        } else {
+            self.volume * self.ducking
+        }
+    }
+}
+
+impl Bus {
+    /// Return the volume of the bus, between 0.0 and 1.0.
+    /// Mute, solo and ducking are not taken into account.
+    pub fn get_volume(&self) -> f64 {
+        self.levels.volume
+    }
+
+    /// Return true if the bus is muted.
+    pub fn is_muted(&self) -> bool {
+        self.levels.muted
+    }
+
+    /// Return true if the bus is soloed.
+    pub fn is_solo(&self) -> bool {
+        self.levels.solo
+    }
+
+    /// Return the volume multiplier applied by [duck()](struct.Mixer.html#method.duck).
+    pub fn get_ducking(&self) -> f64 {
+        self.levels.ducking
+    }
+
+    /// You can use the gain node to insert your own Web Audio nodes.
+    pub fn get_gain_node(&self) -> &GainNode {
+        &self.node
+    }
+}
+
+/// A mixer built on the [Web Audio API](https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API).
+/// Every [Sound](../sound/struct.Sound.html) and [Music](../music/struct.Music.html) is routed through a named [Bus](struct.Bus.html),
+/// and every bus is routed through the master gain.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::audio::{mixer::Mixer, sound::Sound, music::Music};
+///
+/// # async fn test() {
+/// let mut mixer = Mixer::new().unwrap();
+///
+/// let mut jump = Sound::load("https://example.com/jump.ogg").await.unwrap();
+/// let mut theme = Music::load("https://example.com/theme.mp3").await.unwrap();
+/// mixer.route_sound(&jump, "sfx").unwrap();
+/// mixer.route_music(&theme, "music").unwrap();
+///
+/// // values coming from the settings menu
+/// mixer.set_master_volume(0.8);
+/// mixer.set_volume("music", 0.5);
+///
+/// theme.play();
+/// jump.play();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Mixer {
+    context: AudioContext,
+    master: GainNode,
+    master_volume: f64,
+    buses: HashMap<String, Bus>,
+}
+
+impl Mixer {
+    /// Create a mixer with the [default buses](constant.DEFAULT_BUSES.html): "sfx", "music" and "ui".
+    pub fn new() -> Result<Mixer, JsValue> {
+        let context = AudioContext::new()?;
+        let master = context.create_gain()?;
+        master.connect_with_audio_node(&context.destination())?;
+
+        let mut mixer = Mixer {
+            context,
+            master,
+            master_volume: 1.0,
+            buses: HashMap::new(),
+        };
+        for name in DEFAULT_BUSES.iter() {
+            mixer.add_bus(name)?;
+        }
+
+        Ok(mixer)
+    }
+
+    /// Add a new bus.
+    /// Nothing happens if a bus with the same name already exists.
+    pub fn add_bus(&mut self, name: &str) -> Result<(), JsValue> {
+        if !self.buses.contains_key(name) {
+            let node = self.context.create_gain()?;
+            node.connect_with_audio_node(&self.master)?;
+            self.buses.insert(name.to_string(), Bus {
+                node,
+                levels: Levels::default(),
+            });
+            self.update_bus(name, Duration::from_secs(0));
+        }
+        Ok(())
+    }
+
+    /// Return a bus.
+    pub fn get_bus(&self, name: &str) -> Option<&Bus> {
+        self.buses.get(name)
+    }
+
+    /// Return the names of every bus.
+    pub fn get_bus_names(&self) -> Vec<&str> {
+        self.buses.keys().map(|name| name.as_str()).collect()
+    }
+
+    /// Route a [Sound](../sound/struct.Sound.html) through a bus.
+    /// A sound can only be routed once.
+    pub fn route_sound(&mut self, sound: &Sound, bus: &str) -> Result<(), JsValue> {
+        self.route_element(sound.get_html_element(), bus)
+    }
+
+    /// Route a [Music](../music/struct.Music.html) through a bus.
+    /// A music can only be routed once.
+    pub fn route_music(&mut self, music: &Music, bus: &str) -> Result<(), JsValue> {
+        self.route_element(music.get_html_element(), bus)
+    }
+
+    /// Route a [Sound](../sound/struct.Sound.html) through a bus, with a pan and a gain depending on its position in the world.
+    /// See [Emitter](../positional/struct.Emitter.html) for an example.
+    /// A sound can only be routed once.
+    pub fn route_positional_sound(&mut self, sound: &Sound, bus: &str) -> Result<Emitter, JsValue> {
+        let input = self.get_bus_input(bus)?;
+        let source = self.context.create_media_element_source(sound.get_html_element())?;
+        let gain = self.context.create_gain()?;
+        let panner = self.context.create_stereo_panner()?;
+        source.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&panner)?;
+        panner.connect_with_audio_node(&input)?;
+        Ok(Emitter::new(gain, panner))
+    }
+
+    fn route_element(&mut self, element: &HtmlMediaElement, bus: &str) -> Result<(), JsValue> {
+        let input = self.get_bus_input(bus)?;
+        let source = self.context.create_media_element_source(element)?;
+        source.connect_with_audio_node(&input)?;
+        Ok(())
+    }
+
+    fn get_bus_input(&self, bus: &str) -> Result<AudioNode, JsValue> {
+        match self.buses.get(bus) {
+            Some(bus) => Ok(bus.node.clone().into()),
+            None => Err(JsValue::from_str(&format!("unknown bus: {}", bus))),
+        }
+    }
+
+    /// Set the master volume, between 0.0 and 1.0.
+    pub fn set_master_volume(&mut self, volume: f64) {
+        self.master_volume = volume.clamp(0.0, 1.0);
+        ramp(&self.master.gain(), self.master_volume, self.context.current_time(), Duration::from_secs(0));
+    }
+
+    /// Return the master volume, between 0.0 and 1.0.
+    pub fn get_master_volume(&self) -> f64 {
+        self.master_volume
+    }
+
+    /// Return the volume at which the sounds of a bus are heard, between 0.0 and 1.0.
+    /// The master volume, the volume of the bus, mute, solo and ducking are taken into account.
+    pub fn get_effective_volume(&self, bus: &str) -> Option<f64> {
+        let bus = self.buses.get(bus)?;
+        Some(output_gain(self.master_volume, &bus.levels, self.any_solo()))
+    }
+
+    /// Set the volume of a bus, between 0.0 and 1.0.
+    pub fn set_volume(&mut self, bus: &str, volume: f64) {
+        if let Some(levels) = self.get_levels_mut(bus) {
+            levels.volume = volume.clamp(0.0, 1.0);
+            self.update_bus(bus, Duration::from_secs(0));
+        }
+    }
+
+    /// Mute or unmute a bus.
+    pub fn set_muted(&mut self, bus: &str, muted: bool) {
+        if let Some(levels) = self.get_levels_mut(bus) {
+            levels.muted = muted;
+            self.update_bus(bus, Duration::from_secs(0));
+        }
+    }
+
+    /// Solo or unsolo a bus.
+    /// While at least one bus is soloed, the buses which are not soloed are silent.
+    pub fn set_solo(&mut self, bus: &str, solo: bool) {
+        if let Some(levels) = self.get_levels_mut(bus) {
+            if levels.solo != solo {
+                levels.solo = solo;
+                // the gain of every bus depends on the soloed buses
+                self.update_gains(Duration::from_secs(0));
+            }
+        }
+    }
+
+    /// Progressively lower the volume of a bus (for example the music while a character is talking).
+    /// The volume of the bus is multiplied by `factor` (between 0.0 and 1.0) after `fade`.
+    /// Use [unduck()](#method.unduck) to restore the volume.
+    pub fn duck(&mut self, bus: &str, factor: f64, fade: Duration) {
+        if let Some(levels) = self.get_levels_mut(bus) {
+            levels.ducking = factor.clamp(0.0, 1.0);
+            self.update_bus(bus, fade);
+        }
+    }
+
+    /// Progressively restore the volume of a bus lowered by [duck()](#method.duck).
+    pub fn unduck(&mut self, bus: &str, fade: Duration) {
+        self.duck(bus, 1.0, fade);
+    }
+
+    /// Resume the audio context.
+    /// Browsers suspend audio contexts created before any user interaction, so you may want to call this in a click handler.
+    pub fn resume(&self) {
+        // the returned promise is rejected if the browser still refuses to play audio
+        let _ = self.context.resume();
+    }
+
+    /// You can use the audio context to create your own Web Audio nodes.
+    pub fn get_audio_context(&self) -> &AudioContext {
+        &self.context
+    }
+
+    fn get_levels_mut(&mut self, bus: &str) -> Option<&mut Levels> {
+        self.buses.get_mut(bus).map(|bus| &mut bus.levels)
+    }
+
+    fn any_solo(&self) -> bool {
+        self.buses.values().any(|bus| bus.levels.solo)
+    }
+
+    /// Move the gain of one bus to its new value.
+    /// The other buses are left untouched so that their fades go on.
+    fn update_bus(&self, name: &str, fade: Duration) {
+        if let Some(bus) = self.buses.get(name) {
+            ramp(&bus.node.gain(), bus.levels.gain(self.any_solo()), self.context.current_time(), fade);
+        }
+    }
+
+    fn update_gains(&self, fade: Duration) {
+        let any_solo = self.any_solo();
+        let now = self.context.current_time();
+        for bus in self.buses.values() {
+            ramp(&bus.node.gain(), bus.levels.gain(any_solo), now, fade);
+        }
+    }
+}
+
+/// Return the volume at which the sounds of a bus are heard.
+fn output_gain(master_volume: f64, levels: &Levels, any_solo: bool) -> f64 {
+    master_volume * levels.gain(any_solo)
+}
+
+/// Move an audio param to a value without clicks.
+pub(crate) fn ramp(param: &AudioParam, value: f64, now: f64, fade: Duration) {
+    let _ = param.cancel_scheduled_values(now);
+    if fade.as_secs_f64() > 0.0 {
+        let _ = param.set_value_at_time(param.value(), now);
+        let _ = param.linear_ramp_to_value_at_time(value as f32, now + fade.as_secs_f64());
+    } else {
+        let _ = param.set_value_at_time(value as f32, now);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gains() {
+        let levels = Levels { volume: 0.5, ..Levels::default() };
+        assert_eq!(output_gain(1.0, &levels, false), 0.5);
+        assert_eq!(output_gain(0.5, &levels, false), 0.25);
+
+        // ducking multiplies the volume
+        let ducked = Levels { ducking: 0.5, ..levels };
+        assert_eq!(output_gain(0.8, &ducked, false), 0.2);
+
+        // muted buses are silent
+        let muted = Levels { muted: true, ..levels };
+        assert_eq!(output_gain(1.0, &muted, false), 0.0);
+
+        // only soloed buses are heard while a bus is soloed
+        let solo = Levels { solo: true, ..levels };
+        assert_eq!(output_gain(1.0, &levels, true), 0.0);
+        assert_eq!(output_gain(1.0, &solo, true), 0.5);
+        assert_eq!(output_gain(1.0, &Levels { muted: true, ..solo }, true), 0.0);
+    }
+}
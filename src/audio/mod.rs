@@ -0,0 +1,12 @@
+//! This module contains everything related to audio (sound effects, music and mixing).
+#![allow(missing_docs)]
+
+mod element;
+pub mod sound;
+pub mod music;
+pub mod mixer;
+pub mod positional;
+
+pub use sound::Sound;
+pub use music::Music;
+pub use mixer::Mixer;
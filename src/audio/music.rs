@@ -0,0 +1,110 @@
+use super::element::AudioElement;
+use web_sys::HtmlAudioElement;
+use futures::channel::oneshot::Sender;
+use wasm_bindgen::JsValue;
+use std::time::Duration;
+
+/// This struct represent a long audio track, like a background music.
+/// Unlike a [Sound](../sound/struct.Sound.html), a Music is streamed: it is returned as soon as the browser can start playing it
+/// and the rest of the file is downloaded during the playback.
+/// 
+/// # Example
+/// 
+/// ```rust
+/// use wasm_game_lib::audio::music::Music;
+/// 
+/// # async fn test() {
+/// // start streaming a music
+/// let mut theme = Music::load("https://example.com/theme.mp3").await.unwrap();
+/// 
+/// // play it forever
+/// theme.set_loop(true);
+/// theme.play();
+/// # }
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Music {
+    element: AudioElement,
+}
+
+impl Music {
+    /// Start loading a Music.
+    /// The future completes as soon as enough data is available to start the playback.
+    /// Return a Result because it may fail.
+    pub async fn load(url: &str) -> Result<Music, JsValue> {
+        Ok(Music {
+            element: AudioElement::load(url, "canplay").await?
+        })
+    }
+
+    /// Start loading a Music and send it trought a [oneshot channel](https://docs.rs/futures/0.3.4/futures/channel/oneshot/fn.channel.html).
+    /// 
+    /// It works exactly like [images](../../graphics/image/struct.Image.html#method.load_and_send) so see that for an example.
+    pub async fn load_and_send(url: &str, sender: Sender<Result<Music, JsValue>>) {
+        let music = Music::load(url).await;
+        sender.send(music).expect("can't send the loaded music trought the oneshot shannel");
+    }
+
+    pub fn get_html_element(&self) -> &HtmlAudioElement {
+        self.element.get_html_element()
+    }
+
+    /// Start or resume the playback.
+    /// Browsers may refuse to play a music before the user interacted with the page.
+    pub fn play(&mut self) {
+        self.element.play();
+    }
+
+    /// Pause the playback.
+    /// Calling [play](#method.play) will resume it from the same position.
+    pub fn pause(&mut self) {
+        self.element.pause();
+    }
+
+    /// Stop the playback and rewind the music to the beginning.
+    pub fn stop(&mut self) {
+        self.element.stop();
+    }
+
+    /// Return true if the music is currently playing.
+    pub fn is_playing(&self) -> bool {
+        self.element.is_playing()
+    }
+
+    /// Set whether the music should restart automatically when it reaches the end.
+    pub fn set_loop(&mut self, looping: bool) {
+        self.element.set_loop(looping);
+    }
+
+    /// Return true if the music will restart automatically when it reaches the end.
+    pub fn is_looping(&self) -> bool {
+        self.element.is_looping()
+    }
+
+    /// Set the volume of the music.
+    /// The value will be clamped between 0.0 (muted) and 1.0 (full volume).
+    pub fn set_volume(&mut self, volume: f64) {
+        self.element.set_volume(volume);
+    }
+
+    /// Return the volume of the music, between 0.0 and 1.0.
+    pub fn get_volume(&self) -> f64 {
+        self.element.get_volume()
+    }
+
+    /// Move the playback to a specific position.
+    pub fn set_playing_offset(&mut self, offset: Duration) {
+        self.element.set_current_time(offset);
+    }
+
+    /// Return the current position of the playback.
+    pub fn get_playing_offset(&self) -> Duration {
+        self.element.get_current_time()
+    }
+
+    /// Return the duration of the music.
+    /// The duration may be unknown while the music is streamed, in this case None is returned.
+    pub fn get_duration(&self) -> Option<Duration> {
+        self.element.get_duration()
+    }
+}
@@ -0,0 +1,199 @@
+use super::mixer::ramp;
+use web_sys::{GainNode, StereoPannerNode, AudioNode};
+use std::time::Duration;
+
+/// The duration of the ramp applied by [Emitter::update()](struct.Emitter.html#method.update) to avoid clicks.
+const SMOOTHING: Duration = Duration::from_millis(15);
+
+/// The point from which positional sounds are heard.
+/// It usually follows the camera or the player.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Listener {
+    /// Where the Listener is located in the world
+    pub coords: (f64, f64),
+    /// The distance rules used to compute pan and gain
+    pub attenuation: Attenuation,
+}
+
+impl Listener {
+    /// Create a listener with the [default attenuation](struct.Attenuation.html#method.default).
+    pub fn new<T: Into<f64>>(coords: (T, T)) -> Listener {
+        Listener {
+            coords: (coords.0.into(), coords.1.into()),
+            attenuation: Attenuation::default(),
+        }
+    }
+
+    /// Set the coordinates.
+    /// Call this every frame with the position of your camera.
+    pub fn set_coords<T: Into<f64>>(&mut self, coords: (T, T)) {
+        self.coords = (coords.0.into(), coords.1.into());
+    }
+
+    /// Return the stereo pan of a sound located at `source`, between -1.0 (left) and 1.0 (right).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use wasm_game_lib::audio::positional::Listener;
+    ///
+    /// let listener = Listener::new((0.0, 0.0));
+    /// assert_eq!(listener.pan((0.0, 100.0)), 0.0);
+    /// assert!(listener.pan((-200.0, 0.0)) < 0.0);
+    /// ```
+    pub fn pan(&self, source: (f64, f64)) -> f64 {
+        compute_pan(self.coords, source, &self.attenuation)
+    }
+
+    /// Return the gain of a sound located at `source`, between 0.0 (inaudible) and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use wasm_game_lib::audio::positional::Listener;
+    ///
+    /// let listener = Listener::new((0.0, 0.0));
+    /// assert_eq!(listener.gain((10.0, 10.0)), 1.0);
+    /// assert_eq!(listener.gain((100_000.0, 0.0)), 0.0);
+    /// ```
+    pub fn gain(&self, source: (f64, f64)) -> f64 {
+        compute_gain(self.coords, source, &self.attenuation)
+    }
+}
+
+/// The distance rules used to compute the pan and the gain of a positional sound.
+/// Distances are in world units (pixels if your world is not scaled).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    /// Sounds closer than this distance are played at full volume
+    pub reference_distance: f64,
+    /// Sounds farther than this distance are silent
+    pub max_distance: f64,
+    /// The horizontal offset at which a sound is fully panned to one side
+    pub pan_distance: f64,
+}
+
+impl Default for Attenuation {
+    fn default() -> Attenuation {
+        Attenuation {
+            reference_distance: 100.0,
+            max_distance: 1500.0,
+            pan_distance: 800.0,
+        }
+    }
+}
+
+/// Return the stereo pan of a sound located at `source` heard from `listener`, between -1.0 (left) and 1.0 (right).
+/// The pan only depends on the horizontal offset.
+pub fn compute_pan(listener: (f64, f64), source: (f64, f64), attenuation: &Attenuation) -> f64 {
+    if attenuation.pan_distance <= 0.0 {
+        return 0.0;
+    }
+    ((source.0 - listener.0) / attenuation.pan_distance).clamp(-1.0, 1.0)
+}
+
+/// Return the gain of a sound located at `source` heard from `listener`, between 0.0 (inaudible) and 1.0.
+/// The gain decreases linearly from the reference distance to the max distance.
+pub fn compute_gain(listener: (f64, f64), source: (f64, f64), attenuation: &Attenuation) -> f64 {
+    let distance = (source.0 - listener.0).hypot(source.1 - listener.1);
+    if distance <= attenuation.reference_distance {
+        1.0
+    } else if distance >= attenuation.max_distance {
+        0.0
+    } else {
+        1.0 - (distance - attenuation.reference_distance) / (attenuation.max_distance - attenuation.reference_distance)
+    }
+}
+
+/// A sound attached to a position in the world.
+/// You can get an Emitter with [Mixer::route_positional_sound()](../mixer/struct.Mixer.html#method.route_positional_sound).
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::audio::{mixer::Mixer, sound::Sound, positional::Listener};
+///
+/// # async fn test() {
+/// let mut mixer = Mixer::new().unwrap();
+/// let mut growl = Sound::load("https://example.com/growl.ogg").await.unwrap();
+/// let mut emitter = mixer.route_positional_sound(&growl, "sfx").unwrap();
+/// let mut listener = Listener::new((0.0, 0.0));
+///
+/// growl.play();
+/// loop {
+///     // the coords of the enemy sprite and of the camera
+///     # let enemy_coords = (300, 40); let camera_coords = (0, 0);
+///     emitter.set_coords(enemy_coords);
+///     listener.set_coords(camera_coords);
+///     emitter.update(&listener);
+///     # break;
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Emitter {
+    gain: GainNode,
+    panner: StereoPannerNode,
+    coords: (f64, f64),
+}
+
+impl Emitter {
+    pub(crate) fn new(gain: GainNode, panner: StereoPannerNode) -> Emitter {
+        Emitter {
+            gain,
+            panner,
+            coords: (0.0, 0.0),
+        }
+    }
+
+    /// Set the coordinates of the sound, for example the coords of a [Sprite](../../graphics/sprite/struct.Sprite.html).
+    /// Changes are applied by [update()](#method.update).
+    pub fn set_coords<T: Into<f64>>(&mut self, coords: (T, T)) {
+        self.coords = (coords.0.into(), coords.1.into());
+    }
+
+    /// Return the coordinates of the sound.
+    pub fn get_coords(&self) -> (f64, f64) {
+        self.coords
+    }
+
+    /// Compute the pan and the gain of the sound from the position of the listener and apply them.
+    ///
+    /// The new values are reached progressively in 15ms to avoid clicks.
+    /// Call this every frame.
+    pub fn update(&self, listener: &Listener) {
+        let now = AudioNode::context(&self.gain).current_time();
+        ramp(&self.panner.pan(), listener.pan(self.coords), now, SMOOTHING);
+        ramp(&self.gain.gain(), listener.gain(self.coords), now, SMOOTHING);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pan() {
+        let attenuation = Attenuation::default();
+
+        assert_eq!(compute_pan((0.0, 0.0), (0.0, 500.0), &attenuation), 0.0);
+        assert_eq!(compute_pan((0.0, 0.0), (400.0, 0.0), &attenuation), 0.5);
+        assert_eq!(compute_pan((100.0, 0.0), (-300.0, 0.0), &attenuation), -0.5);
+        assert_eq!(compute_pan((0.0, 0.0), (5000.0, 0.0), &attenuation), 1.0);
+        assert_eq!(compute_pan((0.0, 0.0), (-5000.0, 0.0), &attenuation), -1.0);
+    }
+
+    #[test]
+    fn gain() {
+        let attenuation = Attenuation {
+            reference_distance: 100.0,
+            max_distance: 300.0,
+            pan_distance: 100.0,
+        };
+
+        assert_eq!(compute_gain((0.0, 0.0), (60.0, 80.0), &attenuation), 1.0);
+        assert_eq!(compute_gain((0.0, 0.0), (0.0, 200.0), &attenuation), 0.5);
+        assert_eq!(compute_gain((10.0, 10.0), (10.0, 310.0), &attenuation), 0.0);
+        assert_eq!(compute_gain((0.0, 0.0), (1000.0, 0.0), &attenuation), 0.0);
+    }
+}
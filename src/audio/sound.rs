@@ -0,0 +1,108 @@
+use super::element::AudioElement;
+use web_sys::HtmlAudioElement;
+use futures::channel::oneshot::Sender;
+use wasm_bindgen::JsValue;
+use std::time::Duration;
+
+/// This struct represent a short sound, like a sound effect.
+/// The Sound is returned once the browser can play it to the end without interruption, so it can be played without any delay.
+/// For long background tracks, use [Music](../music/struct.Music.html) instead.
+/// 
+/// # Example
+/// 
+/// ```rust
+/// use wasm_game_lib::audio::sound::Sound;
+/// 
+/// # async fn test() {
+/// // load a sound from the web
+/// let mut jump = Sound::load("https://example.com/jump.ogg").await.unwrap();
+/// 
+/// // play it at half volume
+/// jump.set_volume(0.5);
+/// jump.play();
+/// # }
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Sound {
+    element: AudioElement,
+}
+
+impl Sound {
+    /// Load a Sound.
+    /// Return a Result because it may fail.
+    /// 
+    /// # Example
+    /// 
+    /// ```rust
+    /// # use wasm_game_lib::audio::sound::Sound;
+    /// # async fn test() {
+    /// let explosion = Sound::load("https://example.com/explosion.wav").await.unwrap();
+    /// # }
+    /// ```
+    pub async fn load(url: &str) -> Result<Sound, JsValue> {
+        Ok(Sound {
+            element: AudioElement::load(url, "canplaythrough").await?
+        })
+    }
+
+    /// Load a Sound and send it trought a [oneshot channel](https://docs.rs/futures/0.3.4/futures/channel/oneshot/fn.channel.html).
+    /// 
+    /// It works exactly like [images](../../graphics/image/struct.Image.html#method.load_and_send) so see that for an example.
+    pub async fn load_and_send(url: &str, sender: Sender<Result<Sound, JsValue>>) {
+        let sound = Sound::load(url).await;
+        sender.send(sound).expect("can't send the loaded sound trought the oneshot shannel");
+    }
+
+    pub fn get_html_element(&self) -> &HtmlAudioElement {
+        self.element.get_html_element()
+    }
+
+    /// Start or resume the playback.
+    /// Browsers may refuse to play a sound before the user interacted with the page.
+    pub fn play(&mut self) {
+        self.element.play();
+    }
+
+    /// Pause the playback.
+    /// Calling [play](#method.play) will resume it from the same position.
+    pub fn pause(&mut self) {
+        self.element.pause();
+    }
+
+    /// Stop the playback and rewind the sound to the beginning.
+    pub fn stop(&mut self) {
+        self.element.stop();
+    }
+
+    /// Return true if the sound is currently playing.
+    pub fn is_playing(&self) -> bool {
+        self.element.is_playing()
+    }
+
+    /// Set whether the sound should restart automatically when it reaches the end.
+    pub fn set_loop(&mut self, looping: bool) {
+        self.element.set_loop(looping);
+    }
+
+    /// Return true if the sound will restart automatically when it reaches the end.
+    pub fn is_looping(&self) -> bool {
+        self.element.is_looping()
+    }
+
+    /// Set the volume of the sound.
+    /// The value will be clamped between 0.0 (muted) and 1.0 (full volume).
+    pub fn set_volume(&mut self, volume: f64) {
+        self.element.set_volume(volume);
+    }
+
+    /// Return the volume of the sound, between 0.0 and 1.0.
+    pub fn get_volume(&self) -> f64 {
+        self.element.get_volume()
+    }
+
+    /// Return the duration of the sound.
+    /// The duration may be unknown if the metadata is not loaded or if the source is streamed, in this case None is returned.
+    pub fn get_duration(&self) -> Option<Duration> {
+        self.element.get_duration()
+    }
+}
@@ -12,8 +12,7 @@ use wasm_bindgen::{JsCast, JsValue};
 /// use wasm_game_lib::graphics::window::Window;
 /// use wasm_game_lib::graphics::image::Image;
 /// use wasm_game_lib::graphics::sprite::Sprite;
-/// use wasm_game_lib::system::sleep;
-/// use std::time::Duration;
+/// use wasm_game_lib::system::next_frame;
 /// 
 /// # async fn test() {
 /// // Create a sprite to demonstrate how to draw a sprite on the canvas
@@ -28,8 +27,8 @@ use wasm_bindgen::{JsCast, JsValue};
 ///     canvas.draw(&sprite);   // draw a sprite on the canvas
 ///     // note that canvas.display() is not needed unlike a lot of graphics libraries
 ///     
-///     // you may want to slow down the loop to keep your game at 60fps
-///     sleep(Duration::from_millis(16)).await; 
+///     // wait for the next frame of the display (see GameLoop for a fixed timestep)
+///     next_frame().await;
 /// }
 /// # }
 /// ```
@@ -211,6 +210,24 @@ impl Canvas {
     pub fn get_size(&self) -> (u32, u32) {
         (self.element.width(), self.element.height())
     }
+
+    /// Convert a position relative to the viewport (like the clientX and clientY of browser events) to a position on the canvas, in canvas pixels.
+    /// The position of the canvas in the page, its CSS border and size and the device pixel ratio are taken into account.
+    pub fn client_to_canvas_coords(&self, coords: (f64, f64)) -> (f64, f64) {
+        client_to_canvas_coords(&self.element, coords)
+    }
+}
+
+pub(crate) fn client_to_canvas_coords(element: &web_sys::HtmlCanvasElement, (x, y): (f64, f64)) -> (f64, f64) {
+    let rect = element.get_bounding_client_rect();
+    // the drawing area starts inside the CSS border, and clientWidth and clientHeight exclude it
+    let left = rect.left() + f64::from(element.client_left());
+    let top = rect.top() + f64::from(element.client_top());
+    let (width, height) = (f64::from(element.client_width()), f64::from(element.client_height()));
+    // the canvas may be scaled by CSS, and its pixels are not CSS pixels when the device pixel ratio is not 1
+    let scale_x = if width > 0.0 { f64::from(element.width()) / width } else { 1.0 };
+    let scale_y = if height > 0.0 { f64::from(element.height()) / height } else { 1.0 };
+    ((x - left) * scale_x, (y - top) * scale_y)
 }
 
 /// An enum representing a [lineCap mode](https://developer.mozilla.org/fr/docs/Web/API/CanvasRenderingContext2D/lineCap).
@@ -9,4 +9,6 @@ pub mod text;
 pub mod sprite;
 pub mod drawable;
 pub mod color;
-pub mod shape;
\ No newline at end of file
+pub mod shape;
+pub mod view;
+pub mod tween;
@@ -0,0 +1,531 @@
+//! Animate values with easing curves.
+//!
+//! An [Animation](trait.Animation.html) gives a value for any time.
+//! The simplest one is a [Tween](struct.Tween.html), going from a value to another with an [Easing](enum.Easing.html) curve.
+//! Animations can be combined in [sequences](struct.Sequence.html), played in [parallel](struct.Parallel.html) and [repeated](struct.Repeat.html).
+//! An [Animator](struct.Animator.html) plays an animation in the game loop.
+//!
+//! # Example
+//!
+//! ```rust
+//! use wasm_game_lib::graphics::tween::{Animation, Animator, Easing, Parallel, Sequence, Tween};
+//! use std::time::Duration;
+//!
+//! // a sprite jumps to the right and then falls, while it fades out and in
+//! let movement = Sequence::new(Tween::new((0.0, 100.0), (50.0, 20.0), Duration::from_millis(500), Easing::QuadOut))
+//!     .then(Tween::new((50.0, 20.0), (100.0, 100.0), Duration::from_millis(500), Easing::BounceOut));
+//! let alpha = Tween::new(1.0, 0.0, Duration::from_millis(500), Easing::SineInOut).yoyo();
+//! let mut animator = Animator::new(Parallel::new(movement, alpha));
+//!
+//! // in the game loop
+//! let (coords, alpha) = animator.update(Duration::from_millis(500));
+//! assert_eq!(coords, (50.0, 20.0));
+//! assert_eq!(alpha, 0.0);
+//! animator.update(Duration::from_millis(500));
+//! assert!(animator.is_finished());
+//! assert_eq!(animator.get_value(), ((100.0, 100.0), 1.0));
+//! ```
+
+use super::color::Color;
+use std::f64::consts::PI;
+use std::fmt;
+use std::time::Duration;
+
+/// An easing curve, changing the speed of an animation over time.
+/// See [easings.net](https://easings.net) to visualize them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Easing {
+    Linear,
+    SineIn,
+    SineOut,
+    SineInOut,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    QuintIn,
+    QuintOut,
+    QuintInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    CircIn,
+    CircOut,
+    CircInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+fn bounce_out(x: f64) -> f64 {
+    const N1: f64 = 7.5625;
+    const D1: f64 = 2.75;
+    if x < 1.0 / D1 {
+        N1 * x * x
+    } else if x < 2.0 / D1 {
+        let x = x - 1.5 / D1;
+        N1 * x * x + 0.75
+    } else if x < 2.5 / D1 {
+        let x = x - 2.25 / D1;
+        N1 * x * x + 0.9375
+    } else {
+        let x = x - 2.625 / D1;
+        N1 * x * x + 0.984_375
+    }
+}
+
+impl Easing {
+    /// Apply the curve to a progression between 0.0 and 1.0.
+    /// The result is 0.0 at the start and 1.0 at the end, but it can go beyond these values in between (back and elastic curves).
+    pub fn apply(self, x: f64) -> f64 {
+        const BACK: f64 = 1.70158;
+        const BACK_IN_OUT: f64 = BACK * 1.525;
+        const ELASTIC: f64 = 2.0 * PI / 3.0;
+        const ELASTIC_IN_OUT: f64 = 2.0 * PI / 4.5;
+
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => x,
+            Easing::SineIn => 1.0 - (x * PI / 2.0).cos(),
+            Easing::SineOut => (x * PI / 2.0).sin(),
+            Easing::SineInOut => -((PI * x).cos() - 1.0) / 2.0,
+            Easing::QuadIn => x.powi(2),
+            Easing::QuadOut => 1.0 - (1.0 - x).powi(2),
+            Easing::QuadInOut if x < 0.5 => 2.0 * x.powi(2),
+            Easing::QuadInOut => 1.0 - (-2.0 * x + 2.0).powi(2) / 2.0,
+            Easing::CubicIn => x.powi(3),
+            Easing::CubicOut => 1.0 - (1.0 - x).powi(3),
+            Easing::CubicInOut if x < 0.5 => 4.0 * x.powi(3),
+            Easing::CubicInOut => 1.0 - (-2.0 * x + 2.0).powi(3) / 2.0,
+            Easing::QuartIn => x.powi(4),
+            Easing::QuartOut => 1.0 - (1.0 - x).powi(4),
+            Easing::QuartInOut if x < 0.5 => 8.0 * x.powi(4),
+            Easing::QuartInOut => 1.0 - (-2.0 * x + 2.0).powi(4) / 2.0,
+            Easing::QuintIn => x.powi(5),
+            Easing::QuintOut => 1.0 - (1.0 - x).powi(5),
+            Easing::QuintInOut if x < 0.5 => 16.0 * x.powi(5),
+            Easing::QuintInOut => 1.0 - (-2.0 * x + 2.0).powi(5) / 2.0,
+            Easing::ExpoIn | Easing::ExpoOut | Easing::ExpoInOut | Easing::ElasticIn | Easing::ElasticOut | Easing::ElasticInOut
+                if x == 0.0 || x == 1.0 => x,
+            Easing::ExpoIn => 2f64.powf(10.0 * x - 10.0),
+            Easing::ExpoOut => 1.0 - 2f64.powf(-10.0 * x),
+            Easing::ExpoInOut if x < 0.5 => 2f64.powf(20.0 * x - 10.0) / 2.0,
+            Easing::ExpoInOut => (2.0 - 2f64.powf(-20.0 * x + 10.0)) / 2.0,
+            Easing::CircIn => 1.0 - (1.0 - x.powi(2)).sqrt(),
+            Easing::CircOut => (1.0 - (x - 1.0).powi(2)).sqrt(),
+            Easing::CircInOut if x < 0.5 => (1.0 - (1.0 - (2.0 * x).powi(2)).sqrt()) / 2.0,
+            Easing::CircInOut => ((1.0 - (-2.0 * x + 2.0).powi(2)).sqrt() + 1.0) / 2.0,
+            Easing::BackIn => (BACK + 1.0) * x.powi(3) - BACK * x.powi(2),
+            Easing::BackOut => 1.0 + (BACK + 1.0) * (x - 1.0).powi(3) + BACK * (x - 1.0).powi(2),
+            Easing::BackInOut if x < 0.5 => (2.0 * x).powi(2) * ((BACK_IN_OUT + 1.0) * 2.0 * x - BACK_IN_OUT) / 2.0,
+            Easing::BackInOut => ((2.0 * x - 2.0).powi(2) * ((BACK_IN_OUT + 1.0) * (x * 2.0 - 2.0) + BACK_IN_OUT) + 2.0) / 2.0,
+            Easing::ElasticIn => -(2f64.powf(10.0 * x - 10.0)) * ((x * 10.0 - 10.75) * ELASTIC).sin(),
+            Easing::ElasticOut => 2f64.powf(-10.0 * x) * ((x * 10.0 - 0.75) * ELASTIC).sin() + 1.0,
+            Easing::ElasticInOut if x < 0.5 => -(2f64.powf(20.0 * x - 10.0) * ((20.0 * x - 11.125) * ELASTIC_IN_OUT).sin()) / 2.0,
+            Easing::ElasticInOut => 2f64.powf(-20.0 * x + 10.0) * ((20.0 * x - 11.125) * ELASTIC_IN_OUT).sin() / 2.0 + 1.0,
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - x),
+            Easing::BounceOut => bounce_out(x),
+            Easing::BounceInOut if x < 0.5 => (1.0 - bounce_out(1.0 - 2.0 * x)) / 2.0,
+            Easing::BounceInOut => (1.0 + bounce_out(2.0 * x - 1.0)) / 2.0,
+        }
+    }
+}
+
+/// A value which can be animated by a [Tween](struct.Tween.html).
+pub trait Tweenable: Copy {
+    /// Return the value between `self` (when `t` is 0.0) and `other` (when `t` is 1.0).
+    /// `t` can be out of this range.
+    fn lerp(self, other: Self, t: f64) -> Self;
+}
+
+impl Tweenable for f64 {
+    fn lerp(self, other: f64, t: f64) -> f64 {
+        self + (other - self) * t
+    }
+}
+
+impl Tweenable for (f64, f64) {
+    fn lerp(self, other: (f64, f64), t: f64) -> (f64, f64) {
+        (self.0.lerp(other.0, t), self.1.lerp(other.1, t))
+    }
+}
+
+impl Tweenable for Color {
+    fn lerp(self, other: Color, t: f64) -> Color {
+        let channel = |a: u8, b: u8| f64::from(a).lerp(f64::from(b), t).round().clamp(0.0, 255.0) as u8;
+        Color {
+            red: channel(self.red, other.red),
+            green: channel(self.green, other.green),
+            blue: channel(self.blue, other.blue),
+            alpha: channel(self.alpha, other.alpha),
+        }
+    }
+}
+
+/// Something giving a value for any time.
+/// Animations are not modified when they are played, use an [Animator](struct.Animator.html) to play them.
+pub trait Animation {
+    /// The type of the animated value
+    type Value;
+
+    /// Return the duration of the animation, or None if it never ends.
+    fn duration(&self) -> Option<Duration>;
+
+    /// Return the value at a time since the start of the animation.
+    /// After the end of the animation, the final value is returned.
+    fn value_at(&self, time: Duration) -> Self::Value;
+
+    /// Play the animation `times` times.
+    fn repeat(self, times: u32) -> Repeat<Self> where Self: Sized {
+        Repeat { animation: self, times: Some(times), yoyo: false }
+    }
+
+    /// Play the animation again and again.
+    fn repeat_forever(self) -> Repeat<Self> where Self: Sized {
+        Repeat { animation: self, times: None, yoyo: false }
+    }
+
+    /// Play the animation forward and then backward.
+    /// Use `yoyo().repeat_forever()` to make it go back and forth forever.
+    fn yoyo(self) -> Repeat<Self> where Self: Sized {
+        Repeat { animation: self, times: Some(2), yoyo: true }
+    }
+}
+
+/// An animation from a value to another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tween<T: Tweenable> {
+    /// The value at the start
+    pub from: T,
+    /// The value at the end
+    pub to: T,
+    /// The duration of the tween
+    pub duration: Duration,
+    /// The curve followed by the value
+    pub easing: Easing,
+}
+
+impl<T: Tweenable> Tween<T> {
+    /// Create a tween.
+    pub fn new(from: T, to: T, duration: Duration, easing: Easing) -> Tween<T> {
+        Tween {
+            from,
+            to,
+            duration,
+            easing,
+        }
+    }
+}
+
+impl<T: Tweenable> Animation for Tween<T> {
+    type Value = T;
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+
+    fn value_at(&self, time: Duration) -> T {
+        if time >= self.duration {
+            return self.to;
+        }
+        let progression = time.as_secs_f64() / self.duration.as_secs_f64();
+        self.from.lerp(self.to, self.easing.apply(progression))
+    }
+}
+
+impl<A: Animation + ?Sized> Animation for Box<A> {
+    type Value = A::Value;
+
+    fn duration(&self) -> Option<Duration> {
+        (**self).duration()
+    }
+
+    fn value_at(&self, time: Duration) -> A::Value {
+        (**self).value_at(time)
+    }
+}
+
+/// Animations played one after the other.
+/// The animations can be of different types (tweens, repeated animations, other sequences...) as long as they animate the same type of value.
+pub struct Sequence<T> {
+    animations: Vec<Box<dyn Animation<Value = T>>>,
+}
+
+impl<T> Sequence<T> {
+    /// Create a sequence starting with an animation.
+    pub fn new(first: impl Animation<Value = T> + 'static) -> Sequence<T> {
+        Sequence { animations: vec![Box::new(first)] }
+    }
+
+    /// Add an animation at the end of the sequence.
+    pub fn then(mut self, animation: impl Animation<Value = T> + 'static) -> Sequence<T> {
+        self.animations.push(Box::new(animation));
+        self
+    }
+}
+
+impl<T> fmt::Debug for Sequence<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sequence")
+            .field("animations", &self.animations.len())
+            .field("duration", &self.duration())
+            .finish()
+    }
+}
+
+impl<T> Animation for Sequence<T> {
+    type Value = T;
+
+    fn duration(&self) -> Option<Duration> {
+        self.animations.iter().map(|animation| animation.duration()).sum()
+    }
+
+    fn value_at(&self, mut time: Duration) -> T {
+        let last = self.animations.len() - 1;
+        for animation in &self.animations[..last] {
+            match animation.duration() {
+                Some(duration) if time >= duration => time -= duration,
+                _ => return animation.value_at(time),
+            }
+        }
+        self.animations[last].value_at(time)
+    }
+}
+
+/// Two animations played at the same time.
+/// The value is a tuple containing the values of both animations.
+/// Groups of more animations can be created by nesting groups.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parallel<A: Animation, B: Animation> {
+    first: A,
+    second: B,
+}
+
+impl<A: Animation, B: Animation> Parallel<A, B> {
+    /// Create a group of two animations.
+    pub fn new(first: A, second: B) -> Parallel<A, B> {
+        Parallel { first, second }
+    }
+}
+
+impl<A: Animation, B: Animation> Animation for Parallel<A, B> {
+    type Value = (A::Value, B::Value);
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.first.duration()?.max(self.second.duration()?))
+    }
+
+    fn value_at(&self, time: Duration) -> (A::Value, B::Value) {
+        (self.first.value_at(time), self.second.value_at(time))
+    }
+}
+
+/// A repeated animation, created by [repeat()](trait.Animation.html#method.repeat), [repeat_forever()](trait.Animation.html#method.repeat_forever) or [yoyo()](trait.Animation.html#method.yoyo).
+/// Animations which never end cannot be repeated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repeat<A: Animation> {
+    animation: A,
+    times: Option<u32>,
+    yoyo: bool,
+}
+
+impl<A: Animation> Animation for Repeat<A> {
+    type Value = A::Value;
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.animation.duration()? * self.times?)
+    }
+
+    fn value_at(&self, time: Duration) -> A::Value {
+        let duration = match self.animation.duration() {
+            Some(duration) if duration > Duration::from_secs(0) => duration,
+            _ => return self.animation.value_at(time),
+        };
+        let mut play = time.as_nanos() / duration.as_nanos();
+        let mut time_in_play = Duration::from_nanos((time.as_nanos() % duration.as_nanos()) as u64);
+        if let Some(times) = self.times {
+            if play >= u128::from(times) {
+                // stay at the end of the last play
+                play = u128::from(times.max(1) - 1);
+                time_in_play = duration;
+            }
+        }
+        if self.yoyo && play % 2 == 1 {
+            time_in_play = duration - time_in_play;
+        }
+        self.animation.value_at(time_in_play)
+    }
+}
+
+/// Plays an [Animation](trait.Animation.html).
+/// Call [update()](#method.update) once per frame.
+#[derive(Debug, Clone)]
+pub struct Animator<A: Animation> {
+    /// The animation being played
+    pub animation: A,
+    time: Duration,
+}
+
+impl<A: Animation> Animator<A> {
+    /// Start playing an animation.
+    pub fn new(animation: A) -> Animator<A> {
+        Animator {
+            animation,
+            time: Duration::from_secs(0),
+        }
+    }
+
+    /// Advance the animation and return the new value.
+    pub fn update(&mut self, delta: Duration) -> A::Value {
+        self.time += delta;
+        self.get_value()
+    }
+
+    /// Return the current value.
+    pub fn get_value(&self) -> A::Value {
+        self.animation.value_at(self.time)
+    }
+
+    /// Return the time since the start of the animation.
+    pub fn get_time(&self) -> Duration {
+        self.time
+    }
+
+    /// Jump to a time of the animation.
+    pub fn set_time(&mut self, time: Duration) {
+        self.time = time;
+    }
+
+    /// Restart the animation.
+    pub fn reset(&mut self) {
+        self.time = Duration::from_secs(0);
+    }
+
+    /// Return true if the animation ended.
+    /// Animations repeated forever never end.
+    pub fn is_finished(&self) -> bool {
+        match self.animation.duration() {
+            Some(duration) => self.time >= duration,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EASINGS: [Easing; 31] = [
+        Easing::Linear, Easing::SineIn, Easing::SineOut, Easing::SineInOut,
+        Easing::QuadIn, Easing::QuadOut, Easing::QuadInOut, Easing::CubicIn, Easing::CubicOut, Easing::CubicInOut,
+        Easing::QuartIn, Easing::QuartOut, Easing::QuartInOut, Easing::QuintIn, Easing::QuintOut, Easing::QuintInOut,
+        Easing::ExpoIn, Easing::ExpoOut, Easing::ExpoInOut, Easing::CircIn, Easing::CircOut, Easing::CircInOut,
+        Easing::BackIn, Easing::BackOut, Easing::BackInOut, Easing::ElasticIn, Easing::ElasticOut, Easing::ElasticInOut,
+        Easing::BounceIn, Easing::BounceOut, Easing::BounceInOut,
+    ];
+
+    fn ms(milliseconds: u64) -> Duration {
+        Duration::from_millis(milliseconds)
+    }
+
+    #[test]
+    fn easings() {
+        for easing in EASINGS.iter() {
+            assert!(easing.apply(0.0).abs() < 1e-9, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-9, "{:?}", easing);
+            assert!(easing.apply(0.5) > -0.5 && easing.apply(0.5) < 1.5, "{:?}", easing);
+        }
+        for easing in [Easing::Linear, Easing::SineInOut, Easing::QuadInOut, Easing::CubicInOut, Easing::ExpoInOut, Easing::CircInOut, Easing::BackInOut, Easing::BounceInOut].iter() {
+            assert!((easing.apply(0.5) - 0.5).abs() < 1e-9, "{:?}", easing);
+        }
+        assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+        assert_eq!(Easing::CubicOut.apply(0.5), 0.875);
+        assert!(Easing::BackIn.apply(0.2) < 0.0);
+        assert!(Easing::ElasticOut.apply(0.2) > 1.0);
+    }
+
+    #[test]
+    fn tweens() {
+        let tween = Tween::new(10.0, 20.0, ms(100), Easing::Linear);
+        assert_eq!(tween.value_at(ms(0)), 10.0);
+        assert_eq!(tween.value_at(ms(25)), 12.5);
+        assert_eq!(tween.value_at(ms(500)), 20.0);
+
+        let color = Tween::new(Color::new_with_alpha(0, 100, 255, 0), Color::new(255, 100, 0), ms(10), Easing::Linear);
+        assert_eq!(color.value_at(ms(5)), Color::new_with_alpha(128, 100, 128, 128));
+        let overshoot = Tween::new(Color::new(0, 0, 0), Color::new(255, 255, 255), ms(10), Easing::BackOut);
+        assert_eq!(overshoot.value_at(ms(8)).red, 255);
+    }
+
+    #[test]
+    fn combinations() {
+        let sequence = Sequence::new(Tween::new((0.0, 0.0), (10.0, 0.0), ms(100), Easing::Linear))
+            .then(Tween::new((10.0, 0.0), (10.0, 10.0), ms(100), Easing::Linear));
+        assert_eq!(sequence.duration(), Some(ms(200)));
+        assert_eq!(sequence.value_at(ms(50)), (5.0, 0.0));
+        assert_eq!(sequence.value_at(ms(150)), (10.0, 5.0));
+        assert_eq!(sequence.value_at(ms(300)), (10.0, 10.0));
+
+        let yoyo = Tween::new(0.0, 1.0, ms(100), Easing::Linear).yoyo();
+        assert_eq!(yoyo.duration(), Some(ms(200)));
+        assert_eq!(yoyo.value_at(ms(150)), 0.5);
+        assert_eq!(yoyo.value_at(ms(250)), 0.0);
+
+        let repeat = Tween::new(0.0, 1.0, ms(100), Easing::Linear).repeat(3);
+        assert_eq!(repeat.value_at(ms(250)), 0.5);
+        assert_eq!(repeat.value_at(ms(400)), 1.0);
+
+        let forever = Tween::new(0.0, 1.0, ms(100), Easing::Linear).yoyo().repeat_forever();
+        assert_eq!(forever.duration(), None);
+        assert_eq!(forever.value_at(ms(1050)), 0.5);
+        assert_eq!(forever.value_at(ms(1150)), 0.5);
+        assert_eq!(forever.value_at(ms(1175)), 0.25);
+
+        let parallel = Parallel::new(Tween::new(0.0, 1.0, ms(100), Easing::Linear), yoyo);
+        assert_eq!(parallel.duration(), Some(ms(200)));
+        assert_eq!(parallel.value_at(ms(150)), (1.0, 0.5));
+
+        let mut animator = Animator::new(parallel);
+        animator.update(ms(100));
+        assert!(!animator.is_finished());
+        assert_eq!(animator.update(ms(100)), (1.0, 0.0));
+        assert!(animator.is_finished());
+        animator.reset();
+        assert_eq!(animator.get_value(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn mixed_combinators() {
+        // a tween, then a yoyo, then a nested sequence
+        let sequence = Sequence::new(Tween::new(0.0, 1.0, ms(100), Easing::Linear))
+            .then(Tween::new(1.0, 2.0, ms(100), Easing::Linear).yoyo())
+            .then(Sequence::new(Tween::new(1.0, 3.0, ms(100), Easing::Linear)).then(Tween::new(3.0, 0.0, ms(100), Easing::Linear).repeat(2)));
+        assert_eq!(sequence.duration(), Some(ms(600)));
+        assert_eq!(sequence.value_at(ms(50)), 0.5);
+        assert_eq!(sequence.value_at(ms(150)), 1.5);
+        assert_eq!(sequence.value_at(ms(250)), 1.5);
+        assert_eq!(sequence.value_at(ms(350)), 2.0);
+        assert_eq!(sequence.value_at(ms(450)), 1.5);
+        assert_eq!(sequence.value_at(ms(550)), 1.5);
+        assert_eq!(sequence.value_at(ms(700)), 0.0);
+
+        // a sequence can be repeated and played in parallel
+        let boxed: Box<dyn Animation<Value = f64>> = Box::new(sequence.repeat(2));
+        assert_eq!(boxed.duration(), Some(ms(1200)));
+        let mut animator = Animator::new(Parallel::new(boxed, Tween::new(0.0, 10.0, ms(1200), Easing::Linear)));
+        assert_eq!(animator.update(ms(600)), (0.0, 5.0));
+        assert_eq!(animator.update(ms(50)).0, 0.5);
+    }
+}
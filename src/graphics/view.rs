@@ -0,0 +1,117 @@
+//! A camera defining which part of the game world is displayed on a canvas.
+
+use super::canvas::Canvas;
+
+/// A 2D camera, like the [View of SFML](https://www.sfml-dev.org/documentation/2.5.1/classsf_1_1View.php).
+/// The point of the world at the center of the view is displayed at the center of the canvas.
+///
+/// Use [apply_on_canvas()](#method.apply_on_canvas) to draw the world through the view,
+/// and [map_pixel_to_coords()](#method.map_pixel_to_coords) to know where the player clicked in the world.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::graphics::view::View;
+///
+/// let mut view = View::new((1000.0, 500.0));
+/// view.zoom = 2.0;
+///
+/// // a click at the center of a 800x600 canvas is at the center of the view
+/// assert_eq!(view.map_pixel_to_coords((400.0, 300.0), (800.0, 600.0)), (1000.0, 500.0));
+/// // everything looks twice bigger
+/// assert_eq!(view.map_pixel_to_coords((500.0, 300.0), (800.0, 600.0)), (1050.0, 500.0));
+/// assert_eq!(view.map_coords_to_pixel((1050.0, 500.0), (800.0, 600.0)), (500.0, 300.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    /// The point of the world displayed at the center of the canvas
+    pub center: (f64, f64),
+    /// The scale of the world: 2.0 displays everything twice bigger
+    pub zoom: f64,
+    /// The rotation of the view in radians
+    pub rotation: f64,
+}
+
+impl View {
+    /// Create a view centered on a point of the world, without zoom or rotation.
+    pub fn new(center: (f64, f64)) -> View {
+        View {
+            center,
+            zoom: 1.0,
+            rotation: 0.0,
+        }
+    }
+
+    /// Move the center of the view.
+    pub fn move_by(&mut self, (dx, dy): (f64, f64)) {
+        self.center = (self.center.0 + dx, self.center.1 + dy);
+    }
+
+    /// Convert a position on a canvas (an event position for example) to a position in the world.
+    /// The canvas size is needed to find its center.
+    pub fn map_pixel_to_coords(&self, (x, y): (f64, f64), (width, height): (f64, f64)) -> (f64, f64) {
+        let (x, y) = ((x - width / 2.0) / self.zoom, (y - height / 2.0) / self.zoom);
+        let (sin, cos) = self.rotation.sin_cos();
+        (self.center.0 + x * cos - y * sin, self.center.1 + x * sin + y * cos)
+    }
+
+    /// Convert a position in the world to a position on a canvas.
+    /// The canvas size is needed to find its center.
+    pub fn map_coords_to_pixel(&self, (x, y): (f64, f64), (width, height): (f64, f64)) -> (f64, f64) {
+        let (x, y) = (x - self.center.0, y - self.center.1);
+        let (sin, cos) = self.rotation.sin_cos();
+        (
+            (x * cos + y * sin) * self.zoom + width / 2.0,
+            (y * cos - x * sin) * self.zoom + height / 2.0,
+        )
+    }
+
+    /// Set the transformation of the canvas so that world positions can be used to draw on it.
+    /// Use [reset_canvas()](#method.reset_canvas) to draw the interface of the game.
+    pub fn apply_on_canvas(&self, canvas: &mut Canvas) {
+        let (width, height) = canvas.get_size();
+        let (width, height) = (f64::from(width), f64::from(height));
+        let (sin, cos) = self.rotation.sin_cos();
+        let (a, b) = (cos * self.zoom, -sin * self.zoom);
+        let (x, y) = self.center;
+        canvas
+            .context
+            .set_transform(a, b, -b, a, width / 2.0 - a * x + b * y, height / 2.0 - b * x - a * y)
+            .unwrap();
+    }
+
+    /// Remove any transformation of the canvas, so that canvas pixels are used to draw on it.
+    pub fn reset_canvas(canvas: &mut Canvas) {
+        canvas.context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap();
+    }
+}
+
+impl Default for View {
+    fn default() -> View {
+        View::new((0.0, 0.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn rotation() {
+        let mut view = View::new((10.0, 20.0));
+        view.rotation = FRAC_PI_2;
+        view.zoom = 0.5;
+
+        // a pixel on the right of the center is below the center in the world
+        assert_close(view.map_pixel_to_coords((60.0, 50.0), (100.0, 100.0)), (10.0, 40.0));
+        for pixel in [(0.0, 0.0), (13.0, 87.5), (100.0, 42.0)].iter() {
+            let coords = view.map_pixel_to_coords(*pixel, (100.0, 100.0));
+            assert_close(view.map_coords_to_pixel(coords, (100.0, 100.0)), *pixel);
+        }
+    }
+}
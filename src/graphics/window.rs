@@ -27,16 +27,21 @@ impl Window {
         canvas.element.set_width(document.document_element().unwrap().client_width() as u32);
         canvas.element.set_height(document.document_element().unwrap().client_height() as u32);
 
+        let mut events = EventManager::new();
+        events.set_canvas(&canvas);
+
         (Window {
             window,
             document,
-            events: EventManager::new()
+            events
         }, canvas)
     }
 
     /// Create a [Canvas](../canvas/struct.Canvas.html) and a Window.
     /// You may specify which [types of event](../../inputs/event/types/index.html) you want to record. 
     /// 
+    /// Since 0.7.0, `events` is a `u16` (it was a `u8`), because there are more than 8 types of event.
+    /// 
     /// # Example
     /// 
     /// ```no_run
@@ -47,12 +52,17 @@ impl Window {
     /// let (window, canvas) = Window::init_with_events(MOUSE_EVENT + KEYBOARD_EVENT + FOCUS_EVENT);
     /// ```
     #[allow(clippy::unreadable_literal)]
-    pub fn init_with_events(events: u8) -> (Window, Canvas) {
-        let mouse_events    = 0b00000001 & events == 0b00000001;
-        let key_events      = 0b00000010 & events == 0b00000010;
-        let size_events     = 0b00000100 & events == 0b00000100;
-        let focus_events    = 0b00001000 & events == 0b00001000;
-        let joystick_events = 0b00010000 & events == 0b00010000;
+    pub fn init_with_events(events: u16) -> (Window, Canvas) {
+        let mouse_events      = 0b0000000000000001 & events == 0b0000000000000001;
+        let key_events        = 0b0000000000000010 & events == 0b0000000000000010;
+        let size_events       = 0b0000000000000100 & events == 0b0000000000000100;
+        let focus_events      = 0b0000000000001000 & events == 0b0000000000001000;
+        let gamepad_events    = 0b0000000000010000 & events == 0b0000000000010000;
+        let clipboard_events  = 0b0000000000100000 & events == 0b0000000000100000;
+        let touch_events      = 0b0000000001000000 & events == 0b0000000001000000;
+        let pointer_events    = 0b0000000010000000 & events == 0b0000000010000000;
+        let text_input_events = 0b0000000100000000 & events == 0b0000000100000000;
+        let lifecycle_events  = 0b0000001000000000 & events == 0b0000001000000000;
 
         let (mut window, canvas) = Window::init();
         if mouse_events {
@@ -67,8 +77,23 @@ impl Window {
         if focus_events {
             window.events.start_recording_focus_events();
         }
-        if joystick_events {
-            unimplemented!("joysticks are not implemented for now");
+        if gamepad_events {
+            window.events.start_recording_gamepad_events();
+        }
+        if clipboard_events {
+            window.events.start_recording_clipboard_events();
+        }
+        if touch_events {
+            window.events.start_recording_touch_events();
+        }
+        if pointer_events {
+            window.events.start_recording_pointer_events();
+        }
+        if text_input_events {
+            window.events.start_recording_text_input_events();
+        }
+        if lifecycle_events {
+            window.events.start_recording_lifecycle_events();
         }
         
         (window, canvas)
@@ -118,6 +143,24 @@ impl Window {
         unimplemented!()
     }
 
+    /// Hide the cursor and lock it on the canvas.
+    /// The mouse can then move without limit, use [RelativeMove](../../inputs/mouse/enum.MouseEvent.html#variant.RelativeMove) events to track it.
+    /// Browsers only accept this shortly after a user interaction (a click, a key press...).
+    /// The user can exit the lock at any time by pressing Escape.
+    pub fn request_pointer_lock(&self, canvas: &Canvas) {
+        canvas.element.request_pointer_lock()
+    }
+
+    /// Release the cursor locked by [request_pointer_lock()](#method.request_pointer_lock).
+    pub fn exit_pointer_lock(&self) {
+        self.document.exit_pointer_lock()
+    }
+
+    /// Return true if the cursor is locked.
+    pub fn is_pointer_locked(&self) -> bool {
+        self.document.pointer_lock_element().is_some()
+    }
+
     /// Return the width of the tab in pixels
     pub fn get_width(&self) -> u32 {
         self.document.document_element().unwrap().client_width() as u32
@@ -0,0 +1,438 @@
+use super::event::Event;
+use super::keyboard::{Key, KeyboardEvent};
+use super::mouse::{Button, MouseEvent};
+use super::gamepad::{GamepadAxis, GamepadButton, GamepadState};
+use super::serialization::{write_string, Tokens};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// A physical input which can be bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Input {
+    /// A key of the keyboard
+    Key(Key),
+    /// A button of the mouse
+    Mouse(Button),
+    /// A button of the gamepad
+    Gamepad(GamepadButton),
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Input::Key(key) => write!(f, "key:{}", *key as u32),
+            Input::Mouse(button) => write!(f, "mouse:{}", *button as i16),
+            Input::Gamepad(button) => write!(f, "gamepad:{}", button.standard_index()),
+        }
+    }
+}
+
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Input, String> {
+        let error = || format!("invalid input: {}", input);
+        let (kind, value) = input.split_once(':').ok_or_else(error)?;
+        match kind {
+            "key" => match Key::from(value.parse::<u32>().map_err(|_| error())?) {
+                Key::Unknow => Err(error()),
+                key => Ok(Input::Key(key)),
+            },
+            "mouse" => Button::try_from(value.parse::<i16>().map_err(|_| error())?).map(Input::Mouse).map_err(|_| error()),
+            "gamepad" => GamepadButton::ALL.get(value.parse::<usize>().map_err(|_| error())?).map(|button| Input::Gamepad(*button)).ok_or_else(error),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// Something which can be bound to an axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AxisInput {
+    /// Two inputs, the first one moving the axis toward -1.0 and the second one toward 1.0
+    Inputs(Input, Input),
+    /// An axis of the gamepad
+    Gamepad(GamepadAxis),
+}
+
+impl fmt::Display for AxisInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AxisInput::Inputs(negative, positive) => write!(f, "{},{}", negative, positive),
+            AxisInput::Gamepad(axis) => write!(f, "gamepad_axis:{}", axis.standard_index()),
+        }
+    }
+}
+
+impl FromStr for AxisInput {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<AxisInput, String> {
+        if let Some((negative, positive)) = input.split_once(',') {
+            return Ok(AxisInput::Inputs(negative.parse()?, positive.parse()?));
+        }
+        match input.split_once(':') {
+            Some(("gamepad_axis", value)) => value
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| GamepadAxis::ALL.get(index))
+                .map(|axis| AxisInput::Gamepad(*axis))
+                .ok_or_else(|| format!("invalid axis input: {}", input)),
+            _ => Err(format!("invalid axis input: {}", input)),
+        }
+    }
+}
+
+/// A layer between physical inputs and the logic of your game.
+/// Named actions ("jump", "fire") and axes ("move_x") are bound to any combination of keys, mouse buttons and gamepad inputs.
+///
+/// Give every event to [handle_event()](#method.handle_event), the gamepad state to [update_gamepad()](#method.update_gamepad),
+/// query the actions, and call [end_frame()](#method.end_frame) at the end of every frame.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::inputs::actions::{ActionMap, Input, AxisInput};
+/// use wasm_game_lib::inputs::event::Event;
+/// use wasm_game_lib::inputs::keyboard::{Key, KeyboardEvent};
+/// use wasm_game_lib::inputs::gamepad::{GamepadButton, GamepadAxis};
+///
+/// let mut actions = ActionMap::new();
+/// actions.bind("jump", Input::Key(Key::Space));
+/// actions.bind("jump", Input::Gamepad(GamepadButton::South));
+/// actions.bind_axis("move_x", AxisInput::Inputs(Input::Key(Key::A), Input::Key(Key::D)));
+/// actions.bind_axis("move_x", AxisInput::Gamepad(GamepadAxis::LeftStickX));
+///
+/// // in the game loop
+/// actions.handle_event(&Event::KeyboardEvent(KeyboardEvent::Down(Key::Space.into())));
+/// actions.handle_event(&Event::KeyboardEvent(KeyboardEvent::Down(Key::D.into())));
+/// assert!(actions.just_pressed("jump"));
+/// assert_eq!(actions.axis_value("move_x"), 1.0);
+/// actions.end_frame();
+///
+/// assert!(actions.is_pressed("jump"));
+/// assert!(!actions.just_pressed("jump"));
+///
+/// // save the bindings chosen by the player
+/// let saved = actions.to_string();
+/// let restored: ActionMap = saved.parse().unwrap();
+/// assert_eq!(restored.get_bindings("jump"), actions.get_bindings("jump"));
+/// ```
+#[derive(Debug, Default)]
+pub struct ActionMap {
+    actions: BTreeMap<String, Vec<Input>>,
+    axes: BTreeMap<String, Vec<AxisInput>>,
+    held: HashSet<Input>,
+    went_down: HashSet<Input>,
+    went_up: HashSet<Input>,
+    gamepad_axes: HashMap<GamepadAxis, f64>,
+    last_input: Option<Input>,
+}
+
+impl ActionMap {
+    /// Create an action map without any binding.
+    pub fn new() -> ActionMap {
+        ActionMap::default()
+    }
+
+    /// Bind an input to an action.
+    pub fn bind(&mut self, action: &str, input: Input) {
+        let bindings = self.actions.entry(action.to_string()).or_default();
+        if !bindings.contains(&input) {
+            bindings.push(input);
+        }
+    }
+
+    /// Remove an input from an action.
+    pub fn unbind(&mut self, action: &str, input: Input) {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|binding| *binding != input);
+        }
+    }
+
+    /// Replace an input of an action by another one, keeping the order of the bindings.
+    pub fn rebind(&mut self, action: &str, old: Input, new: Input) {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            for binding in bindings.iter_mut() {
+                if *binding == old {
+                    *binding = new;
+                }
+            }
+        }
+    }
+
+    /// Remove every input bound to an action.
+    pub fn clear_bindings(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    /// Return the inputs bound to an action.
+    pub fn get_bindings(&self, action: &str) -> &[Input] {
+        self.actions.get(action).map(|bindings| bindings.as_slice()).unwrap_or(&[])
+    }
+
+    /// Bind an input to an axis.
+    pub fn bind_axis(&mut self, axis: &str, input: AxisInput) {
+        let bindings = self.axes.entry(axis.to_string()).or_default();
+        if !bindings.contains(&input) {
+            bindings.push(input);
+        }
+    }
+
+    /// Remove every input bound to an axis.
+    pub fn clear_axis_bindings(&mut self, axis: &str) {
+        self.axes.remove(axis);
+    }
+
+    /// Return the inputs bound to an axis.
+    pub fn get_axis_bindings(&self, axis: &str) -> &[AxisInput] {
+        self.axes.get(axis).map(|bindings| bindings.as_slice()).unwrap_or(&[])
+    }
+
+    /// Return the last input pressed and forget it.
+    /// Useful in a settings menu asking the player to press the key to bind.
+    pub fn take_last_input(&mut self) -> Option<Input> {
+        self.last_input.take()
+    }
+
+    /// Update the state of the inputs with an event.
+    ///
+    /// When the page loses the focus or is hidden, the browser stops sending the releases,
+    /// so every key and mouse button is released (the gamepad buttons are updated by [update_gamepad()](#method.update_gamepad)).
+    /// The other events are ignored.
+    pub fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::KeyboardEvent(KeyboardEvent::Down(info)) => self.press(Input::Key(info.code)),
+            Event::KeyboardEvent(KeyboardEvent::Up(info)) => self.release(Input::Key(info.code)),
+            Event::MouseEvent(MouseEvent::Down(button, _, _)) => self.press(Input::Mouse(*button)),
+            Event::MouseEvent(MouseEvent::Up(button, _, _)) => self.release(Input::Mouse(*button)),
+            Event::FocusEvent(false) | Event::VisibilityChange(false) | Event::PageHide => self.release_keys_and_buttons(),
+            _ => (),
+        }
+    }
+
+    /// Update the state of the gamepad inputs.
+    /// Call this every frame with the state of the gamepad of the player (real or [virtual](../virtual_gamepad/struct.VirtualGamepad.html)).
+    pub fn update_gamepad(&mut self, state: &GamepadState) {
+        for button in GamepadButton::ALL.iter() {
+            let pressed = state.button(*button).pressed;
+            let input = Input::Gamepad(*button);
+            if pressed && !self.held.contains(&input) {
+                self.press(input);
+            } else if !pressed && self.held.contains(&input) {
+                self.release(input);
+            }
+        }
+        for axis in GamepadAxis::ALL.iter() {
+            self.gamepad_axes.insert(*axis, state.axis(*axis));
+        }
+    }
+
+    fn press(&mut self, input: Input) {
+        if self.held.insert(input) {
+            self.went_down.insert(input);
+            self.last_input = Some(input);
+        }
+    }
+
+    fn release(&mut self, input: Input) {
+        if self.held.remove(&input) {
+            self.went_up.insert(input);
+        }
+    }
+
+    fn release_keys_and_buttons(&mut self) {
+        let inputs: Vec<Input> = self
+            .held
+            .iter()
+            .filter(|input| !matches!(input, Input::Gamepad(_)))
+            .copied()
+            .collect();
+        for input in inputs {
+            self.release(input);
+        }
+    }
+
+    /// Forget what happened during this frame.
+    /// Call this at the end of every frame, after querying the actions.
+    pub fn end_frame(&mut self) {
+        self.went_down.clear();
+        self.went_up.clear();
+    }
+
+    /// Return true if at least one input bound to the action is pressed.
+    pub fn is_pressed(&self, action: &str) -> bool {
+        self.get_bindings(action).iter().any(|input| self.held.contains(input))
+    }
+
+    /// Return true if an input bound to the action has been pressed during this frame.
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.get_bindings(action).iter().any(|input| self.went_down.contains(input))
+    }
+
+    /// Return true if the action has been released during this frame and is no longer pressed.
+    pub fn just_released(&self, action: &str) -> bool {
+        !self.is_pressed(action) && self.get_bindings(action).iter().any(|input| self.went_up.contains(input))
+    }
+
+    /// Return the value of an axis, between -1.0 and 1.0.
+    /// When several inputs are bound to the axis, their values are added.
+    pub fn axis_value(&self, axis: &str) -> f64 {
+        let value: f64 = self
+            .get_axis_bindings(axis)
+            .iter()
+            .map(|input| match input {
+                AxisInput::Inputs(negative, positive) => {
+                    let mut value = 0.0;
+                    if self.held.contains(negative) {
+                        value -= 1.0;
+                    }
+                    if self.held.contains(positive) {
+                        value += 1.0;
+                    }
+                    value
+                },
+                AxisInput::Gamepad(axis) => self.gamepad_axes.get(axis).copied().unwrap_or(0.0),
+            })
+            .sum();
+        value.clamp(-1.0, 1.0)
+    }
+}
+
+/// The bindings are serialized, one binding per line.
+/// The state of the inputs is not.
+/// Action and axis names are quoted, using the same format as [Recording](../replay/struct.Recording.html)s.
+impl fmt::Display for ActionMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (action, bindings) in self.actions.iter() {
+            for binding in bindings {
+                write!(f, "action ")?;
+                write_string(f, action)?;
+                writeln!(f, " {}", binding)?;
+            }
+        }
+        for (axis, bindings) in self.axes.iter() {
+            for binding in bindings {
+                write!(f, "axis ")?;
+                write_string(f, axis)?;
+                writeln!(f, " {}", binding)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ActionMap {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<ActionMap, String> {
+        let mut actions = ActionMap::new();
+        for line in text.lines().map(|line| line.trim()).filter(|line| !line.is_empty()) {
+            let mut tokens = Tokens::new(line)?;
+            if tokens.remaining() != 3 {
+                return Err(format!("invalid binding: {}", line));
+            }
+            let (kind, name, input) = (tokens.next_string()?, tokens.next_string()?, tokens.next_string()?);
+            match kind.as_str() {
+                "action" => actions.bind(&name, input.parse()?),
+                "axis" => actions.bind_axis(&name, input.parse()?),
+                _ => return Err(format!("invalid binding: {}", line)),
+            }
+        }
+        Ok(actions)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key_down(key: Key) -> Event {
+        Event::KeyboardEvent(KeyboardEvent::Down(key.into()))
+    }
+
+    fn key_up(key: Key) -> Event {
+        Event::KeyboardEvent(KeyboardEvent::Up(key.into()))
+    }
+
+    #[test]
+    fn frames() {
+        let mut actions = ActionMap::new();
+        actions.bind("jump", Input::Key(Key::Space));
+        actions.bind("jump", Input::Mouse(Button::Main));
+
+        actions.handle_event(&key_down(Key::Space));
+        assert!(actions.is_pressed("jump"));
+        assert!(actions.just_pressed("jump"));
+        assert!(!actions.just_released("jump"));
+        actions.end_frame();
+
+        // key repeats are not new presses
+        actions.handle_event(&key_down(Key::Space));
+        assert!(actions.is_pressed("jump"));
+        assert!(!actions.just_pressed("jump"));
+
+        // the action stays pressed while another input is held
+        actions.handle_event(&Event::MouseEvent(MouseEvent::Down(Button::Main, 0.0, 0.0)));
+        actions.handle_event(&key_up(Key::Space));
+        assert!(actions.is_pressed("jump"));
+        assert!(!actions.just_released("jump"));
+        actions.end_frame();
+
+        actions.handle_event(&Event::MouseEvent(MouseEvent::Up(Button::Main, 0.0, 0.0)));
+        assert!(!actions.is_pressed("jump"));
+        assert!(actions.just_released("jump"));
+        actions.end_frame();
+        assert!(!actions.just_released("jump"));
+        assert_eq!(actions.take_last_input(), Some(Input::Mouse(Button::Main)));
+        assert_eq!(actions.take_last_input(), None);
+    }
+
+    #[test]
+    fn rebinding() {
+        let mut actions = ActionMap::new();
+        actions.bind("fire", Input::Key(Key::F));
+        actions.bind("fire", Input::Gamepad(GamepadButton::RightTrigger));
+        actions.rebind("fire", Input::Key(Key::F), Input::Key(Key::Enter));
+        assert_eq!(actions.get_bindings("fire"), &[Input::Key(Key::Enter), Input::Gamepad(GamepadButton::RightTrigger)]);
+
+        actions.handle_event(&key_down(Key::F));
+        assert!(!actions.is_pressed("fire"));
+        actions.handle_event(&key_down(Key::Enter));
+        assert!(actions.is_pressed("fire"));
+
+        actions.unbind("fire", Input::Key(Key::Enter));
+        assert!(!actions.is_pressed("fire"));
+        actions.clear_bindings("fire");
+        assert!(actions.get_bindings("fire").is_empty());
+    }
+
+    #[test]
+    fn blur_reset() {
+        for event in [Event::FocusEvent(false), Event::VisibilityChange(false), Event::PageHide].iter() {
+            let mut actions = ActionMap::new();
+            actions.bind("walk", Input::Key(Key::D));
+            actions.bind_axis("move_x", AxisInput::Inputs(Input::Key(Key::A), Input::Key(Key::D)));
+
+            actions.handle_event(&key_down(Key::D));
+            actions.end_frame();
+            assert_eq!(actions.axis_value("move_x"), 1.0);
+
+            // the player alt-tabs while walking, the release is never received
+            actions.handle_event(event);
+            assert!(!actions.is_pressed("walk"));
+            assert!(actions.just_released("walk"));
+            assert_eq!(actions.axis_value("move_x"), 0.0);
+            actions.end_frame();
+            assert!(!actions.just_released("walk"));
+        }
+
+        // gaining the focus does not release anything
+        let mut actions = ActionMap::new();
+        actions.bind("walk", Input::Key(Key::D));
+        actions.handle_event(&key_down(Key::D));
+        actions.handle_event(&Event::FocusEvent(true));
+        assert!(actions.is_pressed("walk"));
+    }
+}
@@ -0,0 +1,151 @@
+//! Read and write the system clipboard.
+//!
+//! By default, the [Clipboard API](https://developer.mozilla.org/en-US/docs/Web/API/Clipboard_API) of the browser is used.
+//! You can replace it with another [backend](trait.ClipboardBackend.html) using [set_backend()](fn.set_backend.html),
+//! for example a [MemoryClipboard](struct.MemoryClipboard.html) in your tests.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+/// An event related to the clipboard
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardEvent {
+    /// The user copied something
+    Copy,
+    /// The user cut something
+    Cut,
+    /// The user pasted something, contains the pasted text
+    Paste(String),
+}
+
+/// A future returned by a [ClipboardBackend](trait.ClipboardBackend.html).
+pub type ClipboardFuture<T> = Pin<Box<dyn Future<Output = Result<T, JsValue>>>>;
+
+/// A trait implemented by everything which can be used as a clipboard.
+pub trait ClipboardBackend {
+    /// Return the text contained in the clipboard.
+    fn read_text(&self) -> ClipboardFuture<String>;
+
+    /// Replace the content of the clipboard by a text.
+    fn write_text(&self, text: &str) -> ClipboardFuture<()>;
+}
+
+/// The clipboard of the browser.
+/// Browsers may ask the user for the permission, or refuse to read the clipboard outside of a user interaction.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BrowserClipboard;
+
+impl ClipboardBackend for BrowserClipboard {
+    fn read_text(&self) -> ClipboardFuture<String> {
+        let promise = web_sys::window().unwrap().navigator().clipboard().read_text();
+        Box::pin(async move {
+            let text = JsFuture::from(promise).await?;
+            text.as_string().ok_or_else(|| JsValue::from_str("the clipboard does not contain text"))
+        })
+    }
+
+    fn write_text(&self, text: &str) -> ClipboardFuture<()> {
+        let promise = web_sys::window().unwrap().navigator().clipboard().write_text(text);
+        Box::pin(async move {
+            JsFuture::from(promise).await?;
+            Ok(())
+        })
+    }
+}
+
+/// A clipboard stored in memory.
+/// It can stand in for the browser clipboard in tests.
+/// Clones share the same content.
+/// Like the browser clipboard, an empty clipboard contains an empty text.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::inputs::clipboard::{self, MemoryClipboard};
+/// use futures::executor::block_on;
+///
+/// let memory = MemoryClipboard::new();
+/// clipboard::set_backend(memory.clone());
+///
+/// block_on(clipboard::write_text("seed-1234")).unwrap();
+/// assert_eq!(memory.get_content(), "seed-1234");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MemoryClipboard {
+    content: Rc<RefCell<String>>,
+}
+
+impl MemoryClipboard {
+    /// Create an empty clipboard.
+    pub fn new() -> MemoryClipboard {
+        MemoryClipboard::default()
+    }
+
+    /// Return the content of the clipboard.
+    pub fn get_content(&self) -> String {
+        self.content.borrow().clone()
+    }
+
+    /// Set the content of the clipboard.
+    pub fn set_content(&self, content: String) {
+        *self.content.borrow_mut() = content;
+    }
+}
+
+impl ClipboardBackend for MemoryClipboard {
+    fn read_text(&self) -> ClipboardFuture<String> {
+        let content = self.get_content();
+        Box::pin(async move { Ok(content) })
+    }
+
+    fn write_text(&self, text: &str) -> ClipboardFuture<()> {
+        self.set_content(text.to_string());
+        Box::pin(async { Ok(()) })
+    }
+}
+
+thread_local! {
+    static BACKEND: RefCell<Rc<dyn ClipboardBackend>> = RefCell::new(Rc::new(BrowserClipboard));
+}
+
+/// Replace the clipboard used by [read_text()](fn.read_text.html) and [write_text()](fn.write_text.html).
+pub fn set_backend(backend: impl ClipboardBackend + 'static) {
+    BACKEND.with(|current| *current.borrow_mut() = Rc::new(backend));
+}
+
+/// Return the text contained in the clipboard.
+/// May fail if the clipboard does not contain text or if the user denied the permission.
+pub async fn read_text() -> Result<String, JsValue> {
+    let backend = BACKEND.with(|backend| Rc::clone(&backend.borrow()));
+    backend.read_text().await
+}
+
+/// Replace the content of the clipboard by a text.
+/// May fail if the user denied the permission.
+pub async fn write_text(text: &str) -> Result<(), JsValue> {
+    let backend = BACKEND.with(|backend| Rc::clone(&backend.borrow()));
+    backend.write_text(text).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn memory_backend() {
+        let memory = MemoryClipboard::new();
+        set_backend(memory.clone());
+
+        assert_eq!(block_on(read_text()).unwrap(), "");
+        block_on(write_text("level-42")).unwrap();
+        assert_eq!(block_on(read_text()).unwrap(), "level-42");
+
+        memory.set_content(String::from("pasted"));
+        assert_eq!(block_on(read_text()).unwrap(), "pasted");
+    }
+}
@@ -1,26 +1,44 @@
 /// This module contains every event type.
 /// It is intended to be used in the [init_with_events method](../../../graphics/window/struct.Window.html#method.init_with_events).
+/// Event types are `u16` since 0.7.0 (they were `u8` before), see the changelog to migrate.
 #[allow(clippy::unreadable_literal)]
 pub mod types {
     /// Events related to the mouse
-    pub const MOUSE_EVENT: u8 =     0b00000001;
+    pub const MOUSE_EVENT: u16 =      0b0000000000000001;
     /// Events related to the keyboard
-    pub const KEYBOARD_EVENT: u8 =  0b00000010;
+    pub const KEYBOARD_EVENT: u16 =   0b0000000000000010;
     /// Event fired when the size of the window change
-    pub const RESIZE_EVENT: u8 =    0b00000100;
+    pub const RESIZE_EVENT: u16 =     0b0000000000000100;
     /// Events fired when the window lost or gain focus
-    pub const FOCUS_EVENT: u8 =     0b00001000;
-    /// Joysticks are not supported for now
-    pub const JOYSTICK_EVENT: u8 =  0b00010000;
+    pub const FOCUS_EVENT: u16 =      0b0000000000001000;
+    /// Events related to the gamepads
+    pub const GAMEPAD_EVENT: u16 =    0b0000000000010000;
+    /// Joystick events have been replaced by gamepad events
+    #[deprecated(since = "0.7.0", note = "use GAMEPAD_EVENT instead")]
+    pub const JOYSTICK_EVENT: u16 =   GAMEPAD_EVENT;
+    /// Events fired when the user copy, cut or paste something
+    pub const CLIPBOARD_EVENT: u16 =  0b0000000000100000;
+    /// Events related to touch screens
+    pub const TOUCH_EVENT: u16 =      0b0000000001000000;
+    /// Pointer events (mouse, pen and touch unified)
+    pub const POINTER_EVENT: u16 =    0b0000000010000000;
+    /// Text input and IME composition events
+    pub const TEXT_INPUT_EVENT: u16 = 0b0000000100000000;
+    /// Events fired when the page is hidden, shown or closed
+    pub const LIFECYCLE_EVENT: u16 =  0b0000001000000000;
 }
 
 use super::mouse::*;
 use super::keyboard::*;
-use super::joystick::*;
+use super::gamepad::*;
+use super::clipboard::*;
+use super::touch::*;
+use super::pointer::*;
+use super::text::*;
 use crate::elog;
 
 /// An enum containing more specific enums.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Event {
     /// Event is a mouse event
     MouseEvent(MouseEvent),
@@ -31,22 +49,162 @@ pub enum Event {
     /// The window got or lost focus.
     /// The bool is set to true if the tab has the focus after the modification.
     FocusEvent(bool),
-    /// Joysticks are unsupported
-    JoystickEvent(JoystickEvent)
+    /// Event is a gamepad event
+    GamepadEvent(GamepadEvent),
+    /// Event is a clipboard event
+    ClipboardEvent(ClipboardEvent),
+    /// Event is a touch event
+    TouchEvent(TouchEvent),
+    /// Event is a pointer event
+    PointerEvent(PointerEvent),
+    /// Some text has been typed
+    TextInput(String),
+    /// Event is an IME composition event
+    CompositionEvent(CompositionEvent),
+    /// The tab has been hidden or shown (the user switched tab, minimized the browser...).
+    /// The bool is set to true if the page is visible after the modification.
+    /// This is the best moment to pause the game and save the progress.
+    VisibilityChange(bool),
+    /// The user is leaving the page.
+    /// The page may be unloaded before this event is handled.
+    PageHide,
+    /// The page is about to be unloaded.
+    /// The page may be unloaded before this event is handled.
+    BeforeUnload
 }
 
 use std::rc::Rc;
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::cell::RefCell;
 use wasm_bindgen::{prelude::*, JsCast};
-use web_sys::{window, Window as WebSysWindow};
+use web_sys::{window, EventTarget, Window as WebSysWindow};
 use std::convert::TryFrom;
+use crate::graphics::canvas::{Canvas, client_to_canvas_coords};
+
+type CanvasElement = Rc<RefCell<Option<web_sys::HtmlCanvasElement>>>;
+type TextInputElement = Rc<RefCell<Option<web_sys::HtmlInputElement>>>;
+
+/// Convert viewport coordinates to coordinates on the canvas of an event manager, if it has one.
+fn to_canvas_coords(canvas: &CanvasElement, coords: (f64, f64)) -> (f64, f64) {
+    match &*canvas.borrow() {
+        Some(element) => client_to_canvas_coords(element, coords),
+        None => coords,
+    }
+}
+
+fn mouse_coords(canvas: &CanvasElement, event: &web_sys::MouseEvent) -> (f64, f64) {
+    to_canvas_coords(canvas, (f64::from(event.client_x()), f64::from(event.client_y())))
+}
+
+/// The mouse buttons pressed on the target of an event manager.
+/// When the event manager listens to a canvas, releases are listened on the whole window
+/// so that a button pressed on the canvas and released outside of it does not stay pressed.
+#[derive(Debug, Default)]
+struct PressedButtons(HashSet<Button>);
+
+impl PressedButtons {
+    fn press(&mut self, button: Button) {
+        self.0.insert(button);
+    }
+
+    /// Return true if the button had been pressed on the target, in which case the release must be reported.
+    fn release(&mut self, button: Button) -> bool {
+        self.0.remove(&button)
+    }
+}
+
+/// An event listener added to an element of the page.
+/// The listener is removed when this struct is dropped.
+struct Listener {
+    target: EventTarget,
+    name: &'static str,
+    closure: Box<dyn AsRef<JsValue>>,
+}
+
+impl Listener {
+    fn new<T: ?Sized + 'static>(target: &EventTarget, name: &'static str, closure: Closure<T>) -> Listener {
+        target
+            .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+            .unwrap();
+        Listener {
+            target: target.clone(),
+            name,
+            closure: Box::new(closure),
+        }
+    }
+
+    /// Create a listener which is allowed to prevent the default behavior of the browser.
+    /// Browsers make some listeners passive by default (wheel and touch events on the window).
+    fn new_active<T: ?Sized + 'static>(target: &EventTarget, name: &'static str, closure: Closure<T>) -> Listener {
+        let options = web_sys::AddEventListenerOptions::new();
+        options.set_passive(false);
+        target
+            .add_event_listener_with_callback_and_add_event_listener_options(name, closure.as_ref().unchecked_ref(), &options)
+            .unwrap();
+        Listener {
+            target: target.clone(),
+            name,
+            closure: Box::new(closure),
+        }
+    }
+}
+
+/// The default behaviors of the browser to prevent.
+/// See [EventManager::set_prevented_defaults()](struct.EventManager.html#method.set_prevented_defaults).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PreventedDefaults {
+    /// Keys which must not trigger the default behavior of the browser (scrolling with the arrows and space, moving the focus with tab...)
+    pub keys: HashSet<Key>,
+    /// Prevent the mouse wheel from scrolling or zooming the page
+    pub wheel: bool,
+    /// Prevent the context menu from opening on right click
+    pub context_menu: bool,
+}
+
+impl PreventedDefaults {
+    /// Prevent the behaviors which usually disturb games: scrolling with the keyboard and the wheel, and the context menu.
+    pub fn for_games() -> PreventedDefaults {
+        PreventedDefaults {
+            keys: [
+                Key::Space,
+                Key::LeftArrow,
+                Key::UpArrow,
+                Key::RightArrow,
+                Key::DownArrow,
+                Key::PageUp,
+                Key::PageDown,
+                Key::Home,
+                Key::End,
+            ].iter().copied().collect(),
+            wheel: true,
+            context_menu: true,
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let function: &JsValue = (*self.closure).as_ref();
+        self.target
+            .remove_event_listener_with_callback(self.name, function.unchecked_ref())
+            .unwrap();
+    }
+}
 
 /// The struct which tracks events.
 /// You can get this struct with the [poll_event() method](../../graphics/window/struct.Window.html#method.poll_event), or by creating it [manually](#method.new).
+/// Recording can be stopped with [stop_recording()](#method.stop_recording).
+/// Every listener is removed when the event manager is dropped.
 pub struct EventManager {
     window: WebSysWindow,
-    events: Rc<RefCell<VecDeque<Event>>>
+    target: EventTarget,
+    events: Rc<RefCell<VecDeque<Event>>>,
+    listeners: HashMap<u16, Vec<Listener>>,
+    prevented_defaults: PreventedDefaults,
+    prevent_listeners: Vec<Listener>,
+    canvas: CanvasElement,
+    gamepads: Option<Vec<GamepadState>>,
+    text_input: TextInputElement
 }
 
 impl Default for EventManager {
@@ -59,85 +217,146 @@ impl EventManager {
     /// Create an event manager.
     /// It will not record events if you don't call the appropriate methods.
     pub fn new() -> Self {
+        let window = window().unwrap();
         EventManager {
-            window: window().unwrap(),
-            events: Rc::new(RefCell::new(VecDeque::new()))
+            target: EventTarget::clone(&window),
+            window,
+            events: Rc::new(RefCell::new(VecDeque::new())),
+            listeners: HashMap::new(),
+            prevented_defaults: PreventedDefaults::default(),
+            prevent_listeners: Vec::new(),
+            canvas: Rc::new(RefCell::new(None)),
+            gamepads: None,
+            text_input: Rc::new(RefCell::new(None))
+        }
+    }
+
+    /// Create an event manager listening to the events of a canvas instead of the whole window.
+    /// Clicks on other elements of the page are ignored, and several canvases can have their own event manager.
+    /// Coordinates are relative to the canvas.
+    /// Mouse buttons and pointers pressed on the canvas are still released when the release happens outside of it.
+    ///
+    /// A canvas only receives keyboard events when it has the focus.
+    /// Give a `tab_index` to make it focusable (0 is a good value): it will get the focus when it is clicked.
+    /// Resize events are still recorded on the window.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wasm_game_lib::inputs::event::EventManager;
+    /// use wasm_game_lib::graphics::canvas::Canvas;
+    ///
+    /// let canvas = Canvas::new();
+    /// let mut events = EventManager::for_canvas(&canvas, Some(0));
+    /// events.start_recording_mouse_events();
+    /// events.start_recording_keyboard_events();
+    /// ```
+    pub fn for_canvas(canvas: &Canvas, tab_index: Option<i32>) -> Self {
+        if let Some(tab_index) = tab_index {
+            canvas.element.set_tab_index(tab_index);
         }
+        let mut events = EventManager::new();
+        events.target = EventTarget::clone(&canvas.element);
+        events.set_canvas(canvas);
+        events
+    }
+
+    /// Return true if the event manager listens to an element instead of the whole window.
+    fn is_scoped_to_element(&self) -> bool {
+        self.target.dyn_ref::<web_sys::Element>().is_some()
+    }
+
+    /// Make the coordinates of mouse, touch and pointer events relative to a canvas, in canvas pixels.
+    /// By default, coordinates are relative to the viewport.
+    /// The canvas returned by [Window::init()](../../graphics/window/struct.Window.html#method.init) is used by the event manager of the window.
+    pub fn set_canvas(&mut self, canvas: &Canvas) {
+        *self.canvas.borrow_mut() = Some(canvas.element.clone());
     }
 
     /// The event manager will start recording mouse events.
-    /// This can't be stopped!
     pub fn start_recording_mouse_events(&mut self) {
         use crate::inputs::mouse::*;
 
+        if self.is_recording(types::MOUSE_EVENT) {
+            return;
+        }
+
         let events2 = Rc::clone(&self.events);
+        let canvas2 = Rc::clone(&self.canvas);
         let click = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
-            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Click(event.client_x() as u32, event.client_y() as u32)));
+            let (x, y) = mouse_coords(&canvas2, &event);
+            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Click(x, y)));
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
-        self.window
-            .add_event_listener_with_callback("click", click.as_ref().unchecked_ref())
-            .unwrap();
-        click.forget();
+        self.add_listener(types::MOUSE_EVENT, Listener::new(&self.target, "click", click));
 
         let events2 = Rc::clone(&self.events);
+        let canvas2 = Rc::clone(&self.canvas);
         let event = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
-            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::DoubleClick(event.client_x() as u32, event.client_y() as u32)));
+            let (x, y) = mouse_coords(&canvas2, &event);
+            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::DoubleClick(x, y)));
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
-        self.window
-            .add_event_listener_with_callback("dblclick", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::MOUSE_EVENT, Listener::new(&self.target, "dblclick", event));
+
+        let scoped = self.is_scoped_to_element();
+        let pressed = Rc::new(RefCell::new(PressedButtons::default()));
 
         let events2 = Rc::clone(&self.events);
+        let canvas2 = Rc::clone(&self.canvas);
+        let pressed2 = Rc::clone(&pressed);
         let event = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+            let (x, y) = mouse_coords(&canvas2, &event);
             match Button::try_from(event.button()) {
-                Ok(button) => events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Down(button, event.client_x() as u32, event.client_y() as u32))),
+                Ok(button) => {
+                    pressed2.borrow_mut().press(button);
+                    events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Down(button, x, y)))
+                },
                 Err(n) => elog!("Unknown mouse button pressed: {}", n),
             }       
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
-        self.window
-            .add_event_listener_with_callback("mousedown", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::MOUSE_EVENT, Listener::new(&self.target, "mousedown", event));
 
+        // the button may be released outside of the canvas
+        let release_target = if scoped { EventTarget::clone(&self.window) } else { self.target.clone() };
         let events2 = Rc::clone(&self.events);
+        let canvas2 = Rc::clone(&self.canvas);
         let event = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+            let (x, y) = mouse_coords(&canvas2, &event);
             match Button::try_from(event.button()) {
-                Ok(button) => events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Up(button, event.client_x() as u32, event.client_y() as u32))),
+                Ok(button) => if pressed.borrow_mut().release(button) || !scoped {
+                    events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Up(button, x, y)))
+                },
                 Err(n) => elog!("Unknown mouse button released: {}", n),
             }
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
-        self.window
-            .add_event_listener_with_callback("mouseup", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::MOUSE_EVENT, Listener::new(&release_target, "mouseup", event));
 
         let events2 = Rc::clone(&self.events);
+        let canvas2 = Rc::clone(&self.canvas);
         let event = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
-            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Enter(event.client_x() as u32, event.client_y() as u32)));            
+            let (x, y) = mouse_coords(&canvas2, &event);
+            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Enter(x, y)));            
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
-        self.window
-            .add_event_listener_with_callback("mouseenter", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::MOUSE_EVENT, Listener::new(&self.target, "mouseenter", event));
 
         let events2 = Rc::clone(&self.events);
+        let canvas2 = Rc::clone(&self.canvas);
         let event = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
-            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Leave(event.client_x() as u32, event.client_y() as u32)));            
+            let (x, y) = mouse_coords(&canvas2, &event);
+            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Leave(x, y)));            
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
-        self.window
-            .add_event_listener_with_callback("mouseleave", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::MOUSE_EVENT, Listener::new(&self.target, "mouseleave", event));
 
         let events2 = Rc::clone(&self.events);
+        let canvas2 = Rc::clone(&self.canvas);
         let event = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
-            events2.borrow_mut().push_back(Event::MouseEvent(MouseEvent::Move(event.client_x() as u32, event.client_y() as u32)));
+            let (x, y) = mouse_coords(&canvas2, &event);
+            let mut events = events2.borrow_mut();
+            events.push_back(Event::MouseEvent(MouseEvent::Move(x, y)));
+            if event.movement_x() != 0 || event.movement_y() != 0 {
+                events.push_back(Event::MouseEvent(MouseEvent::RelativeMove(f64::from(event.movement_x()), f64::from(event.movement_y()))));
+            }
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
-        self.window
-            .add_event_listener_with_callback("mousemove", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::MOUSE_EVENT, Listener::new(&self.target, "mousemove", event));
 
         let events2 = Rc::clone(&self.events);
         let event = Closure::wrap(Box::new(move |event: web_sys::WheelEvent| {
@@ -146,73 +365,355 @@ impl EventManager {
                 Err(n) => elog!("Unknown mouse movement: {}", n),
             }
         }) as Box<dyn FnMut(web_sys::WheelEvent)>);
-        self.window
-            .add_event_listener_with_callback("wheel", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::MOUSE_EVENT, Listener::new(&self.target, "wheel", event));
     }
 
     /// The event manager will start recording keyboard events.
-    /// This can't be stopped!
     pub fn start_recording_keyboard_events(&mut self) {
         use crate::inputs::keyboard::*;
 
+        if self.is_recording(types::KEYBOARD_EVENT) {
+            return;
+        }
+
         let events2 = Rc::clone(&self.events);
         let click = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
-            events2.borrow_mut().push_back(Event::KeyboardEvent(KeyboardEvent::Up(Key::from(event.key_code()))))
+            events2.borrow_mut().push_back(Event::KeyboardEvent(KeyboardEvent::Up(KeyInfo::from(&event))))
         }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
-        self.window
-            .add_event_listener_with_callback("keyup", click.as_ref().unchecked_ref())
-            .unwrap();
-        click.forget();
+        self.add_listener(types::KEYBOARD_EVENT, Listener::new(&self.target, "keyup", click));
 
         let events2 = Rc::clone(&self.events);
         let event = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
-            events2.borrow_mut().push_back(Event::KeyboardEvent(KeyboardEvent::Down(Key::from(event.key_code()))));
+            events2.borrow_mut().push_back(Event::KeyboardEvent(KeyboardEvent::Down(KeyInfo::from(&event))));
             
         }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
-        self.window
-            .add_event_listener_with_callback("keydown", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::KEYBOARD_EVENT, Listener::new(&self.target, "keydown", event));
     }
 
     /// The event manager will start recording focus events.
-    /// This can't be stopped!
     pub fn start_recording_focus_events(&mut self) {
+        if self.is_recording(types::FOCUS_EVENT) {
+            return;
+        }
+
         let events2 = Rc::clone(&self.events);
         let event = Closure::wrap(Box::new(move || {
             events2.borrow_mut().push_back(Event::FocusEvent(true))
         }) as Box<dyn FnMut()>);
-        self.window
-            .add_event_listener_with_callback("focus", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::FOCUS_EVENT, Listener::new(&self.target, "focus", event));
 
         let events2 = Rc::clone(&self.events);
         let event = Closure::wrap(Box::new(move || {
             events2.borrow_mut().push_back(Event::FocusEvent(false));
             
         }) as Box<dyn FnMut()>);
-        self.window
-            .add_event_listener_with_callback("blur", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::FOCUS_EVENT, Listener::new(&self.target, "blur", event));
+    }
+
+    /// The event manager will start recording page lifecycle events (visibility changes, page hide and unload).
+    /// These events are always recorded on the whole page.
+    pub fn start_recording_lifecycle_events(&mut self) {
+        if self.is_recording(types::LIFECYCLE_EVENT) {
+            return;
+        }
+
+        let events2 = Rc::clone(&self.events);
+        let event = Closure::wrap(Box::new(move || {
+            let hidden = window().unwrap().document().unwrap().hidden();
+            events2.borrow_mut().push_back(Event::VisibilityChange(!hidden));
+        }) as Box<dyn FnMut()>);
+        let document = self.window.document().unwrap();
+        self.add_listener(types::LIFECYCLE_EVENT, Listener::new(&document, "visibilitychange", event));
+
+        let events2 = Rc::clone(&self.events);
+        let event = Closure::wrap(Box::new(move || {
+            events2.borrow_mut().push_back(Event::PageHide);
+        }) as Box<dyn FnMut()>);
+        self.add_listener(types::LIFECYCLE_EVENT, Listener::new(&self.window, "pagehide", event));
+
+        let events2 = Rc::clone(&self.events);
+        let event = Closure::wrap(Box::new(move || {
+            events2.borrow_mut().push_back(Event::BeforeUnload);
+        }) as Box<dyn FnMut()>);
+        self.add_listener(types::LIFECYCLE_EVENT, Listener::new(&self.window, "beforeunload", event));
     }
 
     /// The event manager will start recording size events.
-    /// This can't be stopped!
     pub fn start_recording_size_events(&mut self) {
+        if self.is_recording(types::RESIZE_EVENT) {
+            return;
+        }
+
         let events2 = Rc::clone(&self.events);
         let event = Closure::wrap(Box::new(move || {
             let width = window().unwrap().document().unwrap().document_element().unwrap().client_width() as u32;
             let height = window().unwrap().document().unwrap().document_element().unwrap().client_height() as u32;
             events2.borrow_mut().push_back(Event::ResizeEvent(width, height));
         }) as Box<dyn FnMut()>);
-        self.window
-            .add_event_listener_with_callback("resize", event.as_ref().unchecked_ref())
-            .unwrap();
-        event.forget();
+        self.add_listener(types::RESIZE_EVENT, Listener::new(&self.window, "resize", event));
+    }
+
+    /// The event manager will start recording touch events.
+    pub fn start_recording_touch_events(&mut self) {
+        if self.is_recording(types::TOUCH_EVENT) {
+            return;
+        }
+
+        for (name, variant) in [
+            ("touchstart", TouchEvent::Start as fn(Vec<Touch>) -> TouchEvent),
+            ("touchmove", TouchEvent::Move),
+            ("touchend", TouchEvent::End),
+            ("touchcancel", TouchEvent::Cancel),
+        ].iter() {
+            let variant = *variant;
+            let events2 = Rc::clone(&self.events);
+            let canvas2 = Rc::clone(&self.canvas);
+            let event = Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
+                let mut touches = changed_touches(&event);
+                for touch in touches.iter_mut() {
+                    touch.coords = to_canvas_coords(&canvas2, touch.coords);
+                }
+                events2.borrow_mut().push_back(Event::TouchEvent(variant(touches)));
+            }) as Box<dyn FnMut(web_sys::TouchEvent)>);
+            self.add_listener(types::TOUCH_EVENT, Listener::new(&self.target, name, event));
+        }
+    }
+
+    /// The event manager will start recording pointer events.
+    pub fn start_recording_pointer_events(&mut self) {
+        if self.is_recording(types::POINTER_EVENT) {
+            return;
+        }
+
+        // capture the pointers pressed on the canvas so that they are released even outside of it
+        let capture_target = self.target.dyn_ref::<web_sys::Element>().cloned();
+        for (name, variant) in [
+            ("pointerdown", PointerEvent::Down as fn(Pointer) -> PointerEvent),
+            ("pointerup", PointerEvent::Up),
+            ("pointermove", PointerEvent::Move),
+            ("pointercancel", PointerEvent::Cancel),
+        ].iter() {
+            let variant = *variant;
+            let events2 = Rc::clone(&self.events);
+            let canvas2 = Rc::clone(&self.canvas);
+            let capture_target = if *name == "pointerdown" { capture_target.clone() } else { None };
+            let event = Closure::wrap(Box::new(move |event: web_sys::PointerEvent| {
+                if let Some(element) = &capture_target {
+                    let _ = element.set_pointer_capture(event.pointer_id());
+                }
+                let mut pointer = Pointer::from(event);
+                pointer.coords = to_canvas_coords(&canvas2, pointer.coords);
+                events2.borrow_mut().push_back(Event::PointerEvent(variant(pointer)));
+            }) as Box<dyn FnMut(web_sys::PointerEvent)>);
+            self.add_listener(types::POINTER_EVENT, Listener::new(&self.target, name, event));
+        }
+    }
+
+    /// The event manager will start recording gamepad events.
+    /// The Gamepad API has no events for buttons and axes so gamepads are polled when the event queue is empty.
+    pub fn start_recording_gamepad_events(&mut self) {
+        if self.gamepads.is_none() {
+            self.gamepads = Some(Vec::new());
+        }
+    }
+
+    /// Choose which default behaviors of the browser are prevented, whatever events are recorded.
+    /// By default, nothing is prevented.
+    /// Keys are never prevented while the [text input](#method.focus_text_input) has the focus, so that the player can type spaces and move the cursor.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wasm_game_lib::inputs::event::{EventManager, PreventedDefaults};
+    /// use wasm_game_lib::inputs::keyboard::Key;
+    ///
+    /// let mut events = EventManager::new();
+    /// let mut prevented = PreventedDefaults::for_games();
+    /// prevented.keys.insert(Key::Tab);
+    /// events.set_prevented_defaults(prevented);
+    /// ```
+    pub fn set_prevented_defaults(&mut self, prevented_defaults: PreventedDefaults) {
+        self.prevent_listeners.clear();
+
+        if !prevented_defaults.keys.is_empty() {
+            let keys = prevented_defaults.keys.clone();
+            let text_input = Rc::clone(&self.text_input);
+            let event = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+                let typing = match (&*text_input.borrow(), event.target()) {
+                    (Some(input), Some(target)) => JsValue::from(input) == JsValue::from(target),
+                    _ => false,
+                };
+                if !typing && keys.contains(&Key::from_code(&event.code())) {
+                    event.prevent_default();
+                }
+            }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+            self.prevent_listeners.push(Listener::new(&self.target, "keydown", event));
+        }
+
+        if prevented_defaults.wheel {
+            let event = Closure::wrap(Box::new(move |event: web_sys::WheelEvent| {
+                event.prevent_default();
+            }) as Box<dyn FnMut(web_sys::WheelEvent)>);
+            self.prevent_listeners.push(Listener::new_active(&self.target, "wheel", event));
+        }
+
+        if prevented_defaults.context_menu {
+            let event = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+                event.prevent_default();
+            }) as Box<dyn FnMut(web_sys::MouseEvent)>);
+            self.prevent_listeners.push(Listener::new(&self.target, "contextmenu", event));
+        }
+
+        self.prevented_defaults = prevented_defaults;
+    }
+
+    /// Return the default behaviors of the browser which are prevented.
+    pub fn get_prevented_defaults(&self) -> &PreventedDefaults {
+        &self.prevented_defaults
+    }
+
+    /// Return true if the event manager is recording a [type of event](types/index.html).
+    pub fn is_recording(&self, event_type: u16) -> bool {
+        match event_type {
+            types::GAMEPAD_EVENT => self.gamepads.is_some(),
+            event_type => self.listeners.contains_key(&event_type),
+        }
+    }
+
+    /// Stop recording some [types of event](types/index.html).
+    /// Events already recorded are kept in the queue.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wasm_game_lib::inputs::event::{EventManager, types::*};
+    ///
+    /// let mut events = EventManager::new();
+    /// events.start_recording_mouse_events();
+    /// events.start_recording_touch_events();
+    ///
+    /// // the game is paused, a menu made of HTML elements is displayed
+    /// events.stop_recording(MOUSE_EVENT | TOUCH_EVENT);
+    /// assert!(!events.is_recording(MOUSE_EVENT));
+    /// ```
+    pub fn stop_recording(&mut self, event_types: u16) {
+        if event_types & types::GAMEPAD_EVENT != 0 {
+            self.gamepads = None;
+        }
+        if event_types & types::TEXT_INPUT_EVENT != 0 {
+            if let Some(input) = self.text_input.borrow_mut().take() {
+                input.remove();
+            }
+        }
+        self.listeners.retain(|event_type, _| event_types & event_type == 0);
+    }
+
+    fn add_listener(&mut self, event_type: u16, listener: Listener) {
+        self.listeners.entry(event_type).or_default().push(listener);
+    }
+
+    /// The event manager will start recording clipboard events (copy, cut and paste).
+    pub fn start_recording_clipboard_events(&mut self) {
+        if self.is_recording(types::CLIPBOARD_EVENT) {
+            return;
+        }
+
+        let events2 = Rc::clone(&self.events);
+        let event = Closure::wrap(Box::new(move || {
+            events2.borrow_mut().push_back(Event::ClipboardEvent(ClipboardEvent::Copy));
+        }) as Box<dyn FnMut()>);
+        self.add_listener(types::CLIPBOARD_EVENT, Listener::new(&self.target, "copy", event));
+
+        let events2 = Rc::clone(&self.events);
+        let event = Closure::wrap(Box::new(move || {
+            events2.borrow_mut().push_back(Event::ClipboardEvent(ClipboardEvent::Cut));
+        }) as Box<dyn FnMut()>);
+        self.add_listener(types::CLIPBOARD_EVENT, Listener::new(&self.target, "cut", event));
+
+        let events2 = Rc::clone(&self.events);
+        let event = Closure::wrap(Box::new(move |event: web_sys::ClipboardEvent| {
+            let text = event.clipboard_data().and_then(|data| data.get_data("text").ok()).unwrap_or_default();
+            events2.borrow_mut().push_back(Event::ClipboardEvent(ClipboardEvent::Paste(text)));
+        }) as Box<dyn FnMut(web_sys::ClipboardEvent)>);
+        self.add_listener(types::CLIPBOARD_EVENT, Listener::new(&self.target, "paste", event));
+    }
+
+    /// The event manager will start recording [text input events](../text/index.html).
+    /// A hidden element is added to the page to receive the text.
+    /// No text is received until [focus_text_input()](#method.focus_text_input) is called.
+    pub fn start_recording_text_input_events(&mut self) {
+        if self.is_recording(types::TEXT_INPUT_EVENT) {
+            return;
+        }
+        let input = create_hidden_input();
+
+        let events2 = Rc::clone(&self.events);
+        let input2 = input.clone();
+        let event = Closure::wrap(Box::new(move |event: web_sys::InputEvent| {
+            // composed text is sent when the composition ends
+            let input_type = event.input_type();
+            if event.is_composing() || input_type == "insertCompositionText" || input_type == "insertFromComposition" {
+                return;
+            }
+            if let Some(text) = event.data() {
+                events2.borrow_mut().push_back(Event::TextInput(text));
+            }
+            input2.set_value("");
+        }) as Box<dyn FnMut(web_sys::InputEvent)>);
+        self.add_listener(types::TEXT_INPUT_EVENT, Listener::new(&input, "input", event));
+
+        let events2 = Rc::clone(&self.events);
+        let event = Closure::wrap(Box::new(move || {
+            events2.borrow_mut().push_back(Event::CompositionEvent(CompositionEvent::Start));
+        }) as Box<dyn FnMut()>);
+        self.add_listener(types::TEXT_INPUT_EVENT, Listener::new(&input, "compositionstart", event));
+
+        let events2 = Rc::clone(&self.events);
+        let event = Closure::wrap(Box::new(move |event: web_sys::CompositionEvent| {
+            events2.borrow_mut().push_back(Event::CompositionEvent(CompositionEvent::Update(event.data().unwrap_or_default())));
+        }) as Box<dyn FnMut(web_sys::CompositionEvent)>);
+        self.add_listener(types::TEXT_INPUT_EVENT, Listener::new(&input, "compositionupdate", event));
+
+        let events2 = Rc::clone(&self.events);
+        let input2 = input.clone();
+        let event = Closure::wrap(Box::new(move |event: web_sys::CompositionEvent| {
+            let text = event.data().unwrap_or_default();
+            let mut events = events2.borrow_mut();
+            events.push_back(Event::CompositionEvent(CompositionEvent::End(text.clone())));
+            if !text.is_empty() {
+                events.push_back(Event::TextInput(text));
+            }
+            input2.set_value("");
+        }) as Box<dyn FnMut(web_sys::CompositionEvent)>);
+        self.add_listener(types::TEXT_INPUT_EVENT, Listener::new(&input, "compositionend", event));
+
+        *self.text_input.borrow_mut() = Some(input);
+    }
+
+    /// Start receiving text.
+    /// On mobile devices, this opens the virtual keyboard if it is called during a user interaction (a click, a touch...).
+    /// Make sure you called [start_recording_text_input_events()](#method.start_recording_text_input_events) before.
+    pub fn focus_text_input(&self) {
+        if let Some(input) = &*self.text_input.borrow() {
+            input.focus().unwrap();
+        } else {
+            elog!("focus_text_input() has been called but text input events are not recorded");
+        }
+    }
+
+    /// Stop receiving text.
+    pub fn blur_text_input(&self) {
+        if let Some(input) = &*self.text_input.borrow() {
+            input.blur().unwrap();
+        }
+    }
+}
+
+impl Drop for EventManager {
+    fn drop(&mut self) {
+        if let Some(input) = self.text_input.borrow_mut().take() {
+            input.remove();
+        }
     }
 }
 
@@ -220,6 +721,36 @@ impl Iterator for EventManager {
     type Item = Event;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.events.borrow().is_empty() {
+            if let Some(gamepads) = &mut self.gamepads {
+                let mut events = self.events.borrow_mut();
+                for event in poll(gamepads) {
+                    events.push_back(Event::GamepadEvent(event));
+                }
+            }
+        }
         self.events.borrow_mut().pop_front()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn release_outside_canvas() {
+        let mut pressed = PressedButtons::default();
+
+        // pressed on the canvas and released anywhere on the window
+        pressed.press(Button::Main);
+        assert!(pressed.release(Button::Main));
+
+        // pressed somewhere else on the page
+        assert!(!pressed.release(Button::Secondary));
+
+        // released once
+        pressed.press(Button::Auxiliary);
+        assert!(pressed.release(Button::Auxiliary));
+        assert!(!pressed.release(Button::Auxiliary));
+    }
+}
@@ -0,0 +1,582 @@
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use wasm_bindgen::JsCast;
+
+/// An event related to a gamepad.
+/// The first field of every variant is the index of the gamepad.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEvent {
+    /// A gamepad has been connected, contains its index and its id
+    Connected(u32, String),
+    /// A gamepad has been disconnected, contains its index and its id
+    Disconnected(u32, String),
+    /// A button has been pressed, contains the index of the gamepad, the index of the button and its analog value (between 0.0 and 1.0)
+    ButtonDown(u32, usize, f64),
+    /// A button has been released, contains the index of the gamepad, the index of the button and its analog value (between 0.0 and 1.0)
+    ButtonUp(u32, usize, f64),
+    /// An axis moved, contains the index of the gamepad, the index of the axis and its new value (between -1.0 and 1.0).
+    /// The [dead zone](fn.set_dead_zone.html) is already applied.
+    AxisMove(u32, usize, f64),
+}
+
+/// The state of a button of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ButtonState {
+    /// True if the button is pressed
+    pub pressed: bool,
+    /// The analog value of the button, between 0.0 and 1.0.
+    /// Buttons which are not analog are either 0.0 or 1.0.
+    pub value: f64,
+}
+
+/// A snapshot of the state of a gamepad.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GamepadState {
+    /// The index of the gamepad
+    pub index: u32,
+    /// A string identifying the model of the gamepad
+    pub id: String,
+    /// True if the browser remapped the gamepad to the [W3C standard layout](https://www.w3.org/TR/gamepad/#remapping)
+    pub standard_mapping: bool,
+    /// The [mapping](fn.get_mapping.html) of the gamepad, resolved when the gamepad is polled.
+    /// None if the layout of the gamepad is unknown.
+    pub mapping: Option<Arc<Mapping>>,
+    /// The state of every button
+    pub buttons: Vec<ButtonState>,
+    /// The value of every axis, between -1.0 and 1.0.
+    /// The [dead zone](fn.set_dead_zone.html) is already applied.
+    pub axes: Vec<f64>,
+}
+
+lazy_static! {
+    static ref DEAD_ZONE: Mutex<f64> = Mutex::new(0.1);
+    static ref VIRTUAL_GAMEPADS: Mutex<Vec<GamepadState>> = Mutex::new(Vec::new());
+}
+
+/// Set the dead zone of the axes, between 0.0 and 1.0.
+/// Axis values whose absolute value is lower than the dead zone are reported as 0.0.
+/// Default is 0.1.
+pub fn set_dead_zone(dead_zone: f64) {
+    *DEAD_ZONE.lock().unwrap() = dead_zone.clamp(0.0, 1.0);
+}
+
+/// Return the dead zone of the axes.
+pub fn get_dead_zone() -> f64 {
+    *DEAD_ZONE.lock().unwrap()
+}
+
+/// Apply a dead zone on an axis value.
+/// Values in the dead zone become 0.0 and the other values are rescaled so that there is no jump at the edge of the dead zone.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::inputs::gamepad::apply_dead_zone;
+///
+/// assert_eq!(apply_dead_zone(0.05, 0.1), 0.0);
+/// assert_eq!(apply_dead_zone(-1.0, 0.1), -1.0);
+/// assert!((apply_dead_zone(0.55, 0.1) - 0.5).abs() < 1e-9);
+/// ```
+pub fn apply_dead_zone(value: f64, dead_zone: f64) -> f64 {
+    if value.abs() <= dead_zone || dead_zone >= 1.0 {
+        0.0
+    } else {
+        value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+    }
+}
+
+/// Plug a gamepad emulated by the library (see [VirtualGamepad](../virtual_gamepad/struct.VirtualGamepad.html)) or update its state.
+/// It is reported by [state()](fn.state.html), [states()](fn.states.html) and the [EventManager](../event/struct.EventManager.html) like a real gamepad.
+pub(crate) fn set_virtual_gamepad(state: GamepadState) {
+    let mut gamepads = VIRTUAL_GAMEPADS.lock().unwrap();
+    match gamepads.iter_mut().find(|gamepad| gamepad.index == state.index) {
+        Some(gamepad) => *gamepad = state,
+        None => gamepads.push(state),
+    }
+}
+
+/// Unplug a gamepad emulated by the library.
+pub(crate) fn remove_virtual_gamepad(index: u32) {
+    VIRTUAL_GAMEPADS.lock().unwrap().retain(|gamepad| gamepad.index != index);
+}
+
+pub(crate) fn virtual_gamepads() -> Vec<GamepadState> {
+    VIRTUAL_GAMEPADS.lock().unwrap().clone()
+}
+
+fn read_gamepads() -> Vec<GamepadState> {
+    let mut gamepads = read_physical_gamepads();
+    gamepads.append(&mut virtual_gamepads());
+    gamepads
+}
+
+fn read_physical_gamepads() -> Vec<GamepadState> {
+    let dead_zone = get_dead_zone();
+    let gamepads = match web_sys::window().unwrap().navigator().get_gamepads() {
+        Ok(gamepads) => gamepads,
+        Err(_) => return Vec::new(),
+    };
+
+    gamepads
+        .iter()
+        .filter_map(|gamepad| gamepad.dyn_into::<web_sys::Gamepad>().ok())
+        .filter(|gamepad| gamepad.connected())
+        .map(|gamepad| {
+            let id = gamepad.id();
+            let standard_mapping = gamepad.mapping() == web_sys::GamepadMappingType::Standard;
+            GamepadState {
+                index: gamepad.index(),
+                mapping: get_mapping(&id, standard_mapping),
+                id,
+                standard_mapping,
+                buttons: gamepad
+                    .buttons()
+                    .iter()
+                    .map(|button| match button.dyn_into::<web_sys::GamepadButton>() {
+                        Ok(button) => ButtonState {
+                            pressed: button.pressed(),
+                            value: button.value(),
+                        },
+                        Err(_) => ButtonState::default(),
+                    })
+                    .collect(),
+                axes: gamepad
+                    .axes()
+                    .iter()
+                    .map(|axis| apply_dead_zone(axis.as_f64().unwrap_or(0.0), dead_zone))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// A button of a gamepad, named after the [W3C standard mapping](https://www.w3.org/TR/gamepad/#remapping).
+/// Face buttons are named after their position to work with every brand of controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// Bottom face button (A on Xbox, Cross on PlayStation)
+    South,
+    /// Right face button (B on Xbox, Circle on PlayStation)
+    East,
+    /// Left face button (X on Xbox, Square on PlayStation)
+    West,
+    /// Top face button (Y on Xbox, Triangle on PlayStation)
+    North,
+    /// LB on Xbox, L1 on PlayStation
+    LeftShoulder,
+    /// RB on Xbox, R1 on PlayStation
+    RightShoulder,
+    /// LT on Xbox, L2 on PlayStation
+    LeftTrigger,
+    /// RT on Xbox, R2 on PlayStation
+    RightTrigger,
+    /// Back/View on Xbox, Share on PlayStation
+    Select,
+    /// Start/Menu on Xbox, Options on PlayStation
+    Start,
+    /// Pressing the left stick
+    LeftStick,
+    /// Pressing the right stick
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    /// The button with the logo of the brand
+    Home,
+}
+
+impl GamepadButton {
+    /// Every button, ordered by their index in the standard mapping.
+    pub const ALL: [GamepadButton; 17] = [
+        GamepadButton::South,
+        GamepadButton::East,
+        GamepadButton::West,
+        GamepadButton::North,
+        GamepadButton::LeftShoulder,
+        GamepadButton::RightShoulder,
+        GamepadButton::LeftTrigger,
+        GamepadButton::RightTrigger,
+        GamepadButton::Select,
+        GamepadButton::Start,
+        GamepadButton::LeftStick,
+        GamepadButton::RightStick,
+        GamepadButton::DPadUp,
+        GamepadButton::DPadDown,
+        GamepadButton::DPadLeft,
+        GamepadButton::DPadRight,
+        GamepadButton::Home,
+    ];
+
+    /// Return the index of the button in the standard mapping.
+    pub fn standard_index(self) -> usize {
+        GamepadButton::ALL.iter().position(|button| *button == self).unwrap()
+    }
+}
+
+/// An axis of a gamepad, named after the [W3C standard mapping](https://www.w3.org/TR/gamepad/#remapping).
+/// Values go from -1.0 (left or up) to 1.0 (right or down).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+}
+
+impl GamepadAxis {
+    /// Every axis, ordered by their index in the standard mapping.
+    pub const ALL: [GamepadAxis; 4] = [
+        GamepadAxis::LeftStickX,
+        GamepadAxis::LeftStickY,
+        GamepadAxis::RightStickX,
+        GamepadAxis::RightStickY,
+    ];
+
+    /// Return the index of the axis in the standard mapping.
+    pub fn standard_index(self) -> usize {
+        GamepadAxis::ALL.iter().position(|axis| *axis == self).unwrap()
+    }
+}
+
+/// Where an axis is located on a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisMapping {
+    /// The raw index of the axis
+    pub index: usize,
+    /// Set to true if the raw values go in the opposite direction
+    pub inverted: bool,
+}
+
+/// A table converting [GamepadButton](enum.GamepadButton.html)s and [GamepadAxis](enum.GamepadAxis.html) to raw indices.
+/// A raw index belongs to at most one button and one axis: assigning an index which is already used unmaps the previous owner.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::inputs::gamepad::{self, Mapping, GamepadButton, GamepadAxis};
+///
+/// // a cheap controller with swapped face buttons and an inverted vertical axis
+/// let mut mapping = Mapping::standard();
+/// mapping.set_button(GamepadButton::South, 2);
+/// mapping.set_button(GamepadButton::West, 0);
+/// mapping.set_axis(GamepadAxis::LeftStickY, 1, true);
+///
+/// // use it for every gamepad whose id contains this string
+/// gamepad::set_mapping_override("Vendor: 0079", mapping);
+///
+/// assert_eq!(gamepad::get_mapping("Generic USB Joystick (Vendor: 0079 Product: 0006)", false).unwrap().get_button_index(GamepadButton::South), Some(2));
+/// assert_eq!(gamepad::get_mapping("Xbox 360 Controller (XInput STANDARD GAMEPAD)", true).unwrap().get_button_index(GamepadButton::South), Some(0));
+/// assert_eq!(gamepad::get_mapping("Unknown Joystick", false), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mapping {
+    buttons: HashMap<GamepadButton, usize>,
+    axes: HashMap<GamepadAxis, AxisMapping>,
+    button_indices: HashMap<usize, GamepadButton>,
+    axis_indices: HashMap<usize, GamepadAxis>,
+}
+
+impl Mapping {
+    /// Create a mapping without any button or axis.
+    pub fn empty() -> Mapping {
+        Mapping {
+            buttons: HashMap::new(),
+            axes: HashMap::new(),
+            button_indices: HashMap::new(),
+            axis_indices: HashMap::new(),
+        }
+    }
+
+    /// Create the W3C standard mapping, used by most browsers for Xbox and PlayStation controllers.
+    pub fn standard() -> Mapping {
+        let mut mapping = Mapping::empty();
+        for button in GamepadButton::ALL.iter() {
+            mapping.set_button(*button, button.standard_index());
+        }
+        for axis in GamepadAxis::ALL.iter() {
+            mapping.set_axis(*axis, axis.standard_index(), false);
+        }
+        mapping
+    }
+
+    /// Set the raw index of a button.
+    /// If another button was using this index, it is unmapped.
+    pub fn set_button(&mut self, button: GamepadButton, index: usize) {
+        self.remove_button(button);
+        if let Some(previous) = self.button_indices.insert(index, button) {
+            self.buttons.remove(&previous);
+        }
+        self.buttons.insert(button, index);
+    }
+
+    /// Set the raw index of an axis.
+    /// If another axis was using this index, it is unmapped.
+    pub fn set_axis(&mut self, axis: GamepadAxis, index: usize, inverted: bool) {
+        if let Some(mapping) = self.axes.remove(&axis) {
+            self.axis_indices.remove(&mapping.index);
+        }
+        if let Some(previous) = self.axis_indices.insert(index, axis) {
+            self.axes.remove(&previous);
+        }
+        self.axes.insert(axis, AxisMapping { index, inverted });
+    }
+
+    /// Remove a button which does not exist on the gamepad.
+    pub fn remove_button(&mut self, button: GamepadButton) {
+        if let Some(index) = self.buttons.remove(&button) {
+            self.button_indices.remove(&index);
+        }
+    }
+
+    /// Return the raw index of a button.
+    pub fn get_button_index(&self, button: GamepadButton) -> Option<usize> {
+        self.buttons.get(&button).copied()
+    }
+
+    /// Return the raw index of an axis.
+    pub fn get_axis_mapping(&self, axis: GamepadAxis) -> Option<AxisMapping> {
+        self.axes.get(&axis).copied()
+    }
+
+    /// Return the button located at a raw index.
+    /// Useful to convert the indices of [GamepadEvent](enum.GamepadEvent.html)s.
+    pub fn get_button(&self, index: usize) -> Option<GamepadButton> {
+        self.button_indices.get(&index).copied()
+    }
+
+    /// Return the axis located at a raw index.
+    pub fn get_axis(&self, index: usize) -> Option<GamepadAxis> {
+        self.axis_indices.get(&index).copied()
+    }
+}
+
+impl Default for Mapping {
+    fn default() -> Mapping {
+        Mapping::standard()
+    }
+}
+
+lazy_static! {
+    static ref STANDARD_MAPPING: Arc<Mapping> = Arc::new(Mapping::standard());
+    static ref MAPPING_OVERRIDES: Mutex<Vec<(String, Arc<Mapping>)>> = Mutex::new(Vec::new());
+}
+
+/// Use a custom [Mapping](struct.Mapping.html) for every gamepad whose id contains `id_pattern`.
+/// Overrides added later take precedence.
+pub fn set_mapping_override(id_pattern: &str, mapping: Mapping) {
+    let mut overrides = MAPPING_OVERRIDES.lock().unwrap();
+    overrides.retain(|(pattern, _)| pattern != id_pattern);
+    overrides.push((id_pattern.to_string(), Arc::new(mapping)));
+}
+
+/// Remove every custom mapping.
+pub fn clear_mapping_overrides() {
+    MAPPING_OVERRIDES.lock().unwrap().clear();
+}
+
+/// Return the [Mapping](struct.Mapping.html) used for a gamepad.
+/// `standard` must be true if the browser reports the standard layout for this gamepad (see [GamepadState::standard_mapping](struct.GamepadState.html#structfield.standard_mapping)).
+///
+/// An [override](fn.set_mapping_override.html) matching the id is always used first.
+/// Otherwise, the standard mapping is returned for standard gamepads and None for the other ones, since their layout is unknown.
+///
+/// The mapping of polled gamepads is already resolved in [GamepadState::mapping](struct.GamepadState.html#structfield.mapping).
+pub fn get_mapping(id: &str, standard: bool) -> Option<Arc<Mapping>> {
+    MAPPING_OVERRIDES
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|(pattern, _)| id.contains(pattern.as_str()))
+        .map(|(_, mapping)| Arc::clone(mapping))
+        .or_else(|| if standard { Some(Arc::clone(&STANDARD_MAPPING)) } else { None })
+}
+
+impl GamepadState {
+    /// Return the state of a button, using the [mapping](fn.get_mapping.html) of this gamepad.
+    pub fn button(&self, button: GamepadButton) -> ButtonState {
+        self.mapping
+            .as_ref()
+            .and_then(|mapping| mapping.get_button_index(button))
+            .and_then(|index| self.buttons.get(index).copied())
+            .unwrap_or_default()
+    }
+
+    /// Return the value of an axis, using the [mapping](fn.get_mapping.html) of this gamepad.
+    pub fn axis(&self, axis: GamepadAxis) -> f64 {
+        match self.mapping.as_ref().and_then(|mapping| mapping.get_axis_mapping(axis)) {
+            Some(mapping) => {
+                let value = self.axes.get(mapping.index).copied().unwrap_or(0.0);
+                if mapping.inverted { -value } else { value }
+            },
+            None => 0.0,
+        }
+    }
+}
+
+/// Return a snapshot of the state of a gamepad, or None if there is no gamepad connected at this index.
+/// Browsers only expose a gamepad after the user pressed one of its buttons.
+/// [Virtual gamepads](../virtual_gamepad/struct.VirtualGamepad.html) are reported too.
+pub fn state(index: u32) -> Option<GamepadState> {
+    read_gamepads().into_iter().find(|gamepad| gamepad.index == index)
+}
+
+/// Return a snapshot of the state of every connected gamepad, including the [virtual ones](../virtual_gamepad/struct.VirtualGamepad.html).
+pub fn states() -> Vec<GamepadState> {
+    read_gamepads()
+}
+
+/// Return true if a button of a gamepad is currently pressed.
+pub fn is_pressed(index: u32, button: usize) -> bool {
+    state(index).and_then(|state| state.buttons.get(button).map(|button| button.pressed)).unwrap_or(false)
+}
+
+/// Return the current value of an axis of a gamepad, between -1.0 and 1.0.
+pub fn get_axis(index: u32, axis: usize) -> f64 {
+    state(index).and_then(|state| state.axes.get(axis).copied()).unwrap_or(0.0)
+}
+
+/// Return true if a [GamepadButton](enum.GamepadButton.html) is currently pressed, using the [mapping](fn.get_mapping.html) of the gamepad.
+pub fn is_button_pressed(index: u32, button: GamepadButton) -> bool {
+    state(index).map(|state| state.button(button).pressed).unwrap_or(false)
+}
+
+/// Return the current value of a [GamepadAxis](enum.GamepadAxis.html), using the [mapping](fn.get_mapping.html) of the gamepad.
+pub fn get_axis_value(index: u32, axis: GamepadAxis) -> f64 {
+    state(index).map(|state| state.axis(axis)).unwrap_or(0.0)
+}
+
+/// Compare two snapshots of the same gamepad and return the events describing the changes.
+/// `None` means that the gamepad is not connected.
+pub(crate) fn diff(previous: Option<&GamepadState>, current: Option<&GamepadState>) -> Vec<GamepadEvent> {
+    let mut events = Vec::new();
+    let default_state = GamepadState::default();
+
+    let current = match (previous, current) {
+        (None, None) => return events,
+        (Some(previous), None) => {
+            events.push(GamepadEvent::Disconnected(previous.index, previous.id.clone()));
+            return events;
+        }
+        (None, Some(current)) => {
+            events.push(GamepadEvent::Connected(current.index, current.id.clone()));
+            current
+        }
+        (Some(_), Some(current)) => current,
+    };
+    let previous = previous.unwrap_or(&default_state);
+
+    for (idx, button) in current.buttons.iter().enumerate() {
+        let was_pressed = previous.buttons.get(idx).map(|button| button.pressed).unwrap_or(false);
+        if button.pressed && !was_pressed {
+            events.push(GamepadEvent::ButtonDown(current.index, idx, button.value));
+        } else if !button.pressed && was_pressed {
+            events.push(GamepadEvent::ButtonUp(current.index, idx, button.value));
+        }
+    }
+
+    for (idx, value) in current.axes.iter().enumerate() {
+        let previous_value = previous.axes.get(idx).copied().unwrap_or(0.0);
+        if (value - previous_value).abs() > f64::EPSILON {
+            events.push(GamepadEvent::AxisMove(current.index, idx, *value));
+        }
+    }
+
+    events
+}
+
+/// Poll the gamepads and return the events describing the changes since the last call.
+/// `previous` is updated with the new snapshots.
+pub(crate) fn poll(previous: &mut Vec<GamepadState>) -> Vec<GamepadEvent> {
+    let current = read_gamepads();
+    let mut events = Vec::new();
+
+    for state in previous.iter() {
+        if !current.iter().any(|gamepad| gamepad.index == state.index) {
+            events.append(&mut diff(Some(state), None));
+        }
+    }
+    for state in current.iter() {
+        events.append(&mut diff(previous.iter().find(|gamepad| gamepad.index == state.index), Some(state)));
+    }
+
+    *previous = current;
+    events
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pad(buttons: &[(bool, f64)], axes: &[f64]) -> GamepadState {
+        GamepadState {
+            index: 0,
+            id: String::from("pad"),
+            standard_mapping: true,
+            mapping: get_mapping("pad", true),
+            buttons: buttons.iter().map(|(pressed, value)| ButtonState { pressed: *pressed, value: *value }).collect(),
+            axes: axes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn connection() {
+        let state = pad(&[(false, 0.0)], &[0.0]);
+
+        assert_eq!(diff(None, Some(&state)), vec![GamepadEvent::Connected(0, String::from("pad"))]);
+        assert_eq!(diff(Some(&state), None), vec![GamepadEvent::Disconnected(0, String::from("pad"))]);
+        assert_eq!(diff(Some(&state), Some(&state)), vec![]);
+    }
+
+    #[test]
+    fn buttons_and_axes() {
+        let before = pad(&[(false, 0.0), (true, 1.0)], &[0.0, 0.5]);
+        let after = pad(&[(true, 0.7), (false, 0.0)], &[-0.3, 0.5]);
+
+        assert_eq!(diff(Some(&before), Some(&after)), vec![
+            GamepadEvent::ButtonDown(0, 0, 0.7),
+            GamepadEvent::ButtonUp(0, 1, 0.0),
+            GamepadEvent::AxisMove(0, 0, -0.3),
+        ]);
+    }
+
+    #[test]
+    fn mapping_reverse_lookup() {
+        let mut mapping = Mapping::standard();
+        assert_eq!(mapping.get_button(0), Some(GamepadButton::South));
+        assert_eq!(mapping.get_axis(1), Some(GamepadAxis::LeftStickY));
+
+        // West takes the index of South, which is unmapped
+        mapping.set_button(GamepadButton::West, 0);
+        assert_eq!(mapping.get_button(0), Some(GamepadButton::West));
+        assert_eq!(mapping.get_button(2), None);
+        assert_eq!(mapping.get_button_index(GamepadButton::South), None);
+
+        mapping.set_button(GamepadButton::South, 2);
+        assert_eq!(mapping.get_button(2), Some(GamepadButton::South));
+
+        mapping.set_axis(GamepadAxis::RightStickX, 1, true);
+        assert_eq!(mapping.get_axis(1), Some(GamepadAxis::RightStickX));
+        assert_eq!(mapping.get_axis(2), None);
+        assert_eq!(mapping.get_axis_mapping(GamepadAxis::LeftStickY), None);
+
+        mapping.remove_button(GamepadButton::South);
+        assert_eq!(mapping.get_button(2), None);
+    }
+
+    #[test]
+    fn non_standard_layout() {
+        let mut state = pad(&[(true, 1.0)], &[0.5]);
+        assert!(state.button(GamepadButton::South).pressed);
+        assert_eq!(state.axis(GamepadAxis::LeftStickX), 0.5);
+
+        state.standard_mapping = false;
+        state.id = String::from("non standard test pad");
+        state.mapping = get_mapping(&state.id, state.standard_mapping);
+        assert_eq!(state.mapping, None);
+        assert!(!state.button(GamepadButton::South).pressed);
+        assert_eq!(state.axis(GamepadAxis::LeftStickX), 0.0);
+    }
+}
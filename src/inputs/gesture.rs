@@ -0,0 +1,353 @@
+use super::touch::{Touch, TouchEvent};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A high-level gesture recognized from [touch events](../touch/enum.TouchEvent.html).
+#[derive(Debug, Clone, PartialEq)]
+pub enum GestureEvent {
+    /// A short touch without movement, contains the position
+    Tap((f64, f64)),
+    /// A second tap shortly after a first one, contains the position.
+    /// The first tap is reported as a normal Tap before.
+    DoubleTap((f64, f64)),
+    /// A long touch without movement, contains the position.
+    /// Fired as soon as the finger has been down long enough, no Tap is fired after it.
+    LongPress((f64, f64)),
+    /// A fast movement of one finger, contains the direction and the speed in pixels per second
+    Swipe(SwipeDirection, f64),
+    /// Two fingers moved closer or apart, contains the scale factor since the last Pinch event and the center of the fingers.
+    /// Multiply your zoom by the factor to follow the fingers.
+    Pinch(f64, (f64, f64)),
+    /// Two fingers moved together, contains the movement since the last Pan event
+    Pan((f64, f64)),
+}
+
+/// The main direction of a [swipe](enum.GestureEvent.html#variant.Swipe)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// The thresholds used by the [GestureRecognizer](struct.GestureRecognizer.html).
+/// Distances are in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureConfig {
+    /// A touch longer than this is not a tap
+    pub tap_max_duration: Duration,
+    /// A finger moving more than this is not a tap nor a long press
+    pub tap_max_distance: f64,
+    /// The maximum delay between the two taps of a double tap
+    pub double_tap_max_delay: Duration,
+    /// The maximum distance between the two taps of a double tap
+    pub double_tap_max_distance: f64,
+    /// How long a finger must stay down to trigger a long press
+    pub long_press_duration: Duration,
+    /// The minimum distance of a swipe
+    pub swipe_min_distance: f64,
+    /// The minimum speed of a swipe, in pixels per second
+    pub swipe_min_velocity: f64,
+}
+
+impl Default for GestureConfig {
+    fn default() -> GestureConfig {
+        GestureConfig {
+            tap_max_duration: Duration::from_millis(250),
+            tap_max_distance: 10.0,
+            double_tap_max_delay: Duration::from_millis(300),
+            double_tap_max_distance: 30.0,
+            long_press_duration: Duration::from_millis(500),
+            swipe_min_distance: 50.0,
+            swipe_min_velocity: 300.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TrackedTouch {
+    start_coords: (f64, f64),
+    start_time: Duration,
+    coords: (f64, f64),
+}
+
+/// A state machine turning [touch events](../touch/enum.TouchEvent.html) into [gestures](enum.GestureEvent.html).
+/// It does not read any clock: you have to give it the time of every event, so it can be used without a browser.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::inputs::gesture::{GestureRecognizer, GestureEvent};
+/// use wasm_game_lib::inputs::touch::{TouchEvent, Touch};
+/// use std::time::Duration;
+///
+/// let mut recognizer = GestureRecognizer::default();
+/// let touch = Touch { identifier: 0, coords: (100.0, 100.0), force: 0.0 };
+///
+/// recognizer.handle_touch_event(&TouchEvent::Start(vec![touch]), Duration::from_millis(1000));
+/// let gestures = recognizer.handle_touch_event(&TouchEvent::End(vec![touch]), Duration::from_millis(1100));
+/// assert_eq!(gestures, vec![GestureEvent::Tap((100.0, 100.0))]);
+/// ```
+///
+/// In a game, the time usually comes from the game loop and [update()](#method.update) is called every frame so that long presses are detected.
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+    /// The thresholds, can be modified at any time
+    pub config: GestureConfig,
+    touches: HashMap<i32, TrackedTouch>,
+    /// Set when more than one finger touched the screen since the last time the screen was released
+    multi_touch: bool,
+    /// Set when the current single touch can no longer be a tap or a long press
+    moved: bool,
+    long_press_fired: bool,
+    last_tap: Option<(Duration, (f64, f64))>,
+    /// The distance between the two fingers and their center at the last Pinch/Pan event
+    two_fingers: Option<(f64, (f64, f64))>,
+}
+
+impl GestureRecognizer {
+    /// Create a recognizer with custom thresholds.
+    pub fn new(config: GestureConfig) -> GestureRecognizer {
+        GestureRecognizer {
+            config,
+            ..GestureRecognizer::default()
+        }
+    }
+
+    /// Feed a touch event happening at `time` and return the recognized gestures.
+    pub fn handle_touch_event(&mut self, event: &TouchEvent, time: Duration) -> Vec<GestureEvent> {
+        let mut gestures = self.update(time);
+
+        match event {
+            TouchEvent::Start(touches) => {
+                for touch in touches {
+                    self.touches.insert(touch.identifier, TrackedTouch {
+                        start_coords: touch.coords,
+                        start_time: time,
+                        coords: touch.coords,
+                    });
+                }
+                if self.touches.len() > 1 {
+                    self.multi_touch = true;
+                }
+                self.two_fingers = self.two_fingers_state();
+            },
+            TouchEvent::Move(touches) => {
+                self.update_touches(touches);
+                if let Some(touch) = self.single_touch() {
+                    if distance(touch.start_coords, touch.coords) > self.config.tap_max_distance {
+                        self.moved = true;
+                    }
+                }
+                if let (Some((last_distance, last_center)), Some((distance, center))) = (self.two_fingers, self.two_fingers_state()) {
+                    if last_distance > 0.0 && (distance - last_distance).abs() > f64::EPSILON {
+                        gestures.push(GestureEvent::Pinch(distance / last_distance, center));
+                    }
+                    if center != last_center {
+                        gestures.push(GestureEvent::Pan((center.0 - last_center.0, center.1 - last_center.1)));
+                    }
+                    self.two_fingers = Some((distance, center));
+                }
+            },
+            TouchEvent::End(touches) => {
+                self.update_touches(touches);
+                if let Some(gesture) = self.recognize_release(time) {
+                    if let GestureEvent::Tap(coords) = gesture {
+                        match self.last_tap {
+                            Some((last_time, last_coords)) if time.saturating_sub(last_time) <= self.config.double_tap_max_delay
+                                && distance(coords, last_coords) <= self.config.double_tap_max_distance => {
+                                gestures.push(GestureEvent::Tap(coords));
+                                gestures.push(GestureEvent::DoubleTap(coords));
+                                self.last_tap = None;
+                            },
+                            _ => {
+                                gestures.push(GestureEvent::Tap(coords));
+                                self.last_tap = Some((time, coords));
+                            },
+                        }
+                    } else {
+                        gestures.push(gesture);
+                    }
+                }
+                self.remove_touches(touches);
+            },
+            TouchEvent::Cancel(touches) => {
+                self.remove_touches(touches);
+            },
+        }
+
+        gestures
+    }
+
+    /// Check the time-based gestures (long press).
+    /// Call this every frame, even when there is no touch event.
+    pub fn update(&mut self, time: Duration) -> Vec<GestureEvent> {
+        let mut gestures = Vec::new();
+        if let Some(touch) = self.single_touch() {
+            if !self.long_press_fired && time.saturating_sub(touch.start_time) >= self.config.long_press_duration {
+                gestures.push(GestureEvent::LongPress(touch.coords));
+                self.long_press_fired = true;
+            }
+        }
+        gestures
+    }
+
+    /// Return the touch if exactly one finger is down, no other finger was involved and it did not move.
+    fn single_touch(&self) -> Option<&TrackedTouch> {
+        if self.moved {
+            return None;
+        }
+        self.only_touch()
+    }
+
+    /// Return the touch if exactly one finger is down and no other finger was involved.
+    fn only_touch(&self) -> Option<&TrackedTouch> {
+        if self.multi_touch || self.touches.len() != 1 {
+            return None;
+        }
+        self.touches.values().next()
+    }
+
+    /// Recognize a tap or a swipe when the last finger is released.
+    fn recognize_release(&self, time: Duration) -> Option<GestureEvent> {
+        let touch = self.only_touch()?;
+        if self.long_press_fired {
+            return None;
+        }
+        let duration = time.saturating_sub(touch.start_time);
+        let (dx, dy) = (touch.coords.0 - touch.start_coords.0, touch.coords.1 - touch.start_coords.1);
+        let moved_distance = dx.hypot(dy);
+
+        if !self.moved && moved_distance <= self.config.tap_max_distance {
+            if duration <= self.config.tap_max_duration {
+                return Some(GestureEvent::Tap(touch.coords));
+            }
+            return None;
+        }
+
+        let duration = duration.as_secs_f64();
+        if moved_distance < self.config.swipe_min_distance || duration <= 0.0 {
+            return None;
+        }
+        let velocity = moved_distance / duration;
+        if velocity < self.config.swipe_min_velocity {
+            return None;
+        }
+        let direction = if dx.abs() > dy.abs() {
+            if dx > 0.0 { SwipeDirection::Right } else { SwipeDirection::Left }
+        } else if dy > 0.0 {
+            SwipeDirection::Down
+        } else {
+            SwipeDirection::Up
+        };
+        Some(GestureEvent::Swipe(direction, velocity))
+    }
+
+    fn update_touches(&mut self, touches: &[Touch]) {
+        for touch in touches {
+            if let Some(tracked) = self.touches.get_mut(&touch.identifier) {
+                tracked.coords = touch.coords;
+            }
+        }
+    }
+
+    fn remove_touches(&mut self, touches: &[Touch]) {
+        for touch in touches {
+            self.touches.remove(&touch.identifier);
+        }
+        self.two_fingers = self.two_fingers_state();
+        if self.touches.is_empty() {
+            self.multi_touch = false;
+            self.moved = false;
+            self.long_press_fired = false;
+        }
+    }
+
+    /// Return the distance between the two fingers and their center, if exactly two fingers are down.
+    fn two_fingers_state(&self) -> Option<(f64, (f64, f64))> {
+        if self.touches.len() != 2 {
+            return None;
+        }
+        let mut touches = self.touches.values();
+        let a = touches.next()?.coords;
+        let b = touches.next()?.coords;
+        Some((distance(a, b), ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)))
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn touch(identifier: i32, x: f64, y: f64) -> Touch {
+        Touch { identifier, coords: (x, y), force: 0.0 }
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn tap_and_double_tap() {
+        let mut recognizer = GestureRecognizer::default();
+
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::Start(vec![touch(0, 10.0, 10.0)]), ms(0)), vec![]);
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::End(vec![touch(0, 12.0, 10.0)]), ms(100)), vec![GestureEvent::Tap((12.0, 10.0))]);
+        recognizer.handle_touch_event(&TouchEvent::Start(vec![touch(1, 15.0, 10.0)]), ms(200));
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::End(vec![touch(1, 15.0, 10.0)]), ms(250)), vec![
+            GestureEvent::Tap((15.0, 10.0)),
+            GestureEvent::DoubleTap((15.0, 10.0)),
+        ]);
+
+        // too slow to be a tap
+        recognizer.handle_touch_event(&TouchEvent::Start(vec![touch(2, 10.0, 10.0)]), ms(1000));
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::End(vec![touch(2, 10.0, 10.0)]), ms(1400)), vec![]);
+    }
+
+    #[test]
+    fn long_press() {
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.handle_touch_event(&TouchEvent::Start(vec![touch(0, 10.0, 10.0)]), ms(0));
+        assert_eq!(recognizer.update(ms(400)), vec![]);
+        assert_eq!(recognizer.update(ms(500)), vec![GestureEvent::LongPress((10.0, 10.0))]);
+        assert_eq!(recognizer.update(ms(600)), vec![]);
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::End(vec![touch(0, 10.0, 10.0)]), ms(700)), vec![]);
+    }
+
+    #[test]
+    fn swipe() {
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.handle_touch_event(&TouchEvent::Start(vec![touch(0, 100.0, 100.0)]), ms(0));
+        recognizer.handle_touch_event(&TouchEvent::Move(vec![touch(0, 50.0, 110.0)]), ms(50));
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::End(vec![touch(0, 0.0, 100.0)]), ms(100)), vec![
+            GestureEvent::Swipe(SwipeDirection::Left, 1000.0),
+        ]);
+
+        // too slow to be a swipe
+        recognizer.handle_touch_event(&TouchEvent::Start(vec![touch(1, 100.0, 100.0)]), ms(1000));
+        recognizer.handle_touch_event(&TouchEvent::Move(vec![touch(1, 100.0, 150.0)]), ms(1500));
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::End(vec![touch(1, 100.0, 200.0)]), ms(2000)), vec![]);
+    }
+
+    #[test]
+    fn pinch_and_pan() {
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.handle_touch_event(&TouchEvent::Start(vec![touch(0, 0.0, 0.0), touch(1, 100.0, 0.0)]), ms(0));
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::Move(vec![touch(0, -50.0, 0.0), touch(1, 150.0, 0.0)]), ms(20)), vec![
+            GestureEvent::Pinch(2.0, (50.0, 0.0)),
+        ]);
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::Move(vec![touch(0, -50.0, 30.0), touch(1, 150.0, 30.0)]), ms(40)), vec![
+            GestureEvent::Pan((0.0, 30.0)),
+        ]);
+        assert_eq!(recognizer.update(ms(1000)), vec![]);
+        assert_eq!(recognizer.handle_touch_event(&TouchEvent::End(vec![touch(0, -50.0, 30.0), touch(1, 150.0, 30.0)]), ms(1100)), vec![]);
+    }
+}
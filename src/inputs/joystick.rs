@@ -1,13 +1,6 @@
-#[derive(Debug)]
+//! Joysticks have been replaced by the [gamepad module](../gamepad/index.html).
+//! This module will be removed in the next release.
+
 /// A joystick event.
-/// Joysticks are not supported for now
-pub enum JoystickEvent {
-    /// A joystick has been connected
-    JoystickConnected(),
-    /// A joystick has been disconnected
-    JoystickDisonnected(),
-    /// A joystick moved
-    JoystickMove(),
-    /// A button has been pressed
-    JoystickButton()
-}
\ No newline at end of file
+#[deprecated(since = "0.7.0", note = "use inputs::gamepad::GamepadEvent instead")]
+pub type JoystickEvent = super::gamepad::GamepadEvent;
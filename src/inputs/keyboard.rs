@@ -1,5 +1,11 @@
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::sync::Mutex;
+use crate::elog;
+
 /// An enum representing all the keys of a keyboard (normally)
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[allow(missing_docs)]
 pub enum Key {
     Unknow = 0,
@@ -12,6 +18,7 @@ pub enum Key {
     Pause = 19,
     CapsLock = 20,
     Escape = 27,
+    Space = 32,
     PageUp = 33,
     PageDown = 34,
     End = 35,
@@ -115,6 +122,7 @@ impl From<u32> for Key {
             19 => Key::Pause,
             20 => Key::CapsLock,
             27 => Key::Escape,
+            32 => Key::Space,
             33 => Key::PageUp,
             34 => Key::PageDown,
             35 => Key::End,
@@ -209,11 +217,328 @@ impl From<u32> for Key {
     }
 }
 
-#[derive(Debug)]
+/// Decode "F1" to "F12", which are both the code and the key of function keys.
+fn function_key(name: &str) -> Option<Key> {
+    match name.strip_prefix('F')?.parse::<u32>() {
+        Ok(n) if (1..=12).contains(&n) => Some(Key::from(111 + n)),
+        _ => None,
+    }
+}
+
+impl Key {
+    /// Decode the [code](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/code) of a keyboard event.
+    /// The code identifies the physical key, whatever the layout of the keyboard is:
+    /// `Key::W` is the key at the position of W on a QWERTY keyboard, which is Z on an AZERTY keyboard.
+    pub fn from_code(code: &str) -> Key {
+        match code {
+            "Backspace" => Key::BackSpace,
+            "Tab" => Key::Tab,
+            "Enter" | "NumpadEnter" => Key::Enter,
+            "ShiftLeft" | "ShiftRight" => Key::Shift,
+            "ControlLeft" | "ControlRight" => Key::Ctrl,
+            "AltLeft" | "AltRight" => Key::Alt,
+            "Pause" => Key::Pause,
+            "CapsLock" => Key::CapsLock,
+            "Escape" => Key::Escape,
+            "Space" => Key::Space,
+            "PageUp" => Key::PageUp,
+            "PageDown" => Key::PageDown,
+            "End" => Key::End,
+            "Home" => Key::Home,
+            "ArrowLeft" => Key::LeftArrow,
+            "ArrowUp" => Key::UpArrow,
+            "ArrowRight" => Key::RightArrow,
+            "ArrowDown" => Key::DownArrow,
+            "Insert" => Key::Insert,
+            "Delete" => Key::Delete,
+            "MetaLeft" | "OSLeft" => Key::LeftWindowKey,
+            "MetaRight" | "OSRight" => Key::RightWindowKey,
+            "ContextMenu" => Key::SelectKey,
+            "NumpadMultiply" => Key::Multiply,
+            "NumpadAdd" => Key::Add,
+            "NumpadSubtract" => Key::Subtract,
+            "NumpadDecimal" => Key::DecimalPoint,
+            "NumpadDivide" => Key::Divide,
+            "NumLock" => Key::NumLock,
+            "ScrollLock" => Key::ScrollLock,
+            "Semicolon" => Key::SemiColon,
+            "Equal" => Key::EqualSign,
+            "Comma" => Key::Comma,
+            "Minus" => Key::Dash,
+            "Period" => Key::Period,
+            "Slash" => Key::ForwardSlash,
+            "Backquote" => Key::GraveAccent,
+            "BracketLeft" => Key::OpenBracket,
+            "Backslash" => Key::BackSlash,
+            "BracketRight" => Key::CloseBraket,
+            "Quote" => Key::SingleQuote,
+            // the key codes of letters, digits and numpad digits match ASCII characters
+            code => match code.as_bytes() {
+                [b'K', b'e', b'y', c @ b'A'..=b'Z'] => Key::from(u32::from(*c)),
+                [b'D', b'i', b'g', b'i', b't', c @ b'0'..=b'9'] => Key::from(u32::from(*c)),
+                [b'N', b'u', b'm', b'p', b'a', b'd', c @ b'0'..=b'9'] => Key::from(u32::from(*c) + 48),
+                _ => function_key(code).unwrap_or(Key::Unknow),
+            },
+        }
+    }
+}
+
+/// The meaning of a key, depending on the layout of the keyboard and on the modifiers.
+/// Decoded from the [key](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key) of a keyboard event.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LogicalKey {
+    /// A key producing a character, for example "a", "A" or "é"
+    Character(String),
+    /// A key which does not produce a character, like Enter or an arrow
+    Named(Key),
+    /// A key which does not produce a character and which is not supported by [Key](enum.Key.html), contains the name given by the browser
+    Other(String),
+}
+
+impl From<&str> for LogicalKey {
+    fn from(key: &str) -> LogicalKey {
+        if key.chars().count() == 1 {
+            return LogicalKey::Character(key.to_string());
+        }
+        let named = match key {
+            "Backspace" => Key::BackSpace,
+            "Tab" => Key::Tab,
+            "Enter" => Key::Enter,
+            "Shift" => Key::Shift,
+            "Control" => Key::Ctrl,
+            "Alt" | "AltGraph" => Key::Alt,
+            "Pause" => Key::Pause,
+            "CapsLock" => Key::CapsLock,
+            "Escape" | "Esc" => Key::Escape,
+            "PageUp" => Key::PageUp,
+            "PageDown" => Key::PageDown,
+            "End" => Key::End,
+            "Home" => Key::Home,
+            "ArrowLeft" | "Left" => Key::LeftArrow,
+            "ArrowUp" | "Up" => Key::UpArrow,
+            "ArrowRight" | "Right" => Key::RightArrow,
+            "ArrowDown" | "Down" => Key::DownArrow,
+            "Insert" => Key::Insert,
+            "Delete" | "Del" => Key::Delete,
+            "Meta" | "OS" => Key::LeftWindowKey,
+            "ContextMenu" => Key::SelectKey,
+            "NumLock" => Key::NumLock,
+            "ScrollLock" => Key::ScrollLock,
+            key => match function_key(key) {
+                Some(key) => key,
+                None => return LogicalKey::Other(key.to_string()),
+            },
+        };
+        LogicalKey::Named(named)
+    }
+}
+
+/// The state of the modifier keys during a keyboard event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    /// A shift key is pressed
+    pub shift: bool,
+    /// A control key is pressed
+    pub ctrl: bool,
+    /// An alt key is pressed
+    pub alt: bool,
+    /// A meta key (Windows key, Command key) is pressed
+    pub meta: bool,
+}
+
+/// Everything known about a key when it is pressed or released.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyInfo {
+    /// The physical key, independent of the layout of the keyboard.
+    /// Use it for controls (WASD...).
+    pub code: Key,
+    /// The meaning of the key, depending on the layout and on the modifiers.
+    /// Use it for shortcuts and text.
+    pub key: LogicalKey,
+    /// The state of the modifier keys
+    pub modifiers: Modifiers,
+    /// True if the event was generated because the key is held down
+    pub repeat: bool,
+}
+
+/// Create the informations of a physical key without modifier.
+/// The logical key is unknown.
+impl From<Key> for KeyInfo {
+    fn from(code: Key) -> KeyInfo {
+        KeyInfo {
+            code,
+            key: LogicalKey::Other(String::from("Unidentified")),
+            modifiers: Modifiers::default(),
+            repeat: false,
+        }
+    }
+}
+
+impl From<&web_sys::KeyboardEvent> for KeyInfo {
+    fn from(event: &web_sys::KeyboardEvent) -> KeyInfo {
+        KeyInfo {
+            code: Key::from_code(&event.code()),
+            key: LogicalKey::from(event.key().as_str()),
+            modifiers: Modifiers {
+                shift: event.shift_key(),
+                ctrl: event.ctrl_key(),
+                alt: event.alt_key(),
+                meta: event.meta_key(),
+            },
+            repeat: event.repeat(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 /// A keyboard event
 pub enum KeyboardEvent {
     /// A key has been pressed
-    Down(Key),
+    Down(KeyInfo),
     /// A key has been released
-    Up(Key)
+    Up(KeyInfo)
+}
+
+lazy_static! {
+    static ref IS_RECORDING_KEYBOARD_EVENTS: AtomicBool = AtomicBool::new(false);
+    static ref IS_SHIFT_PRESSED: AtomicBool = AtomicBool::new(false);
+    static ref IS_CTRL_PRESSED: AtomicBool = AtomicBool::new(false);
+    static ref IS_ALT_PRESSED: AtomicBool = AtomicBool::new(false);
+    static ref IS_META_PRESSED: AtomicBool = AtomicBool::new(false);
+    static ref PRESSED_KEYS: Mutex<HashSet<Key>> = Mutex::new(HashSet::new());
+    static ref KEYS_PRESSED_THIS_FRAME: Mutex<HashSet<Key>> = Mutex::new(HashSet::new());
+}
+
+fn update_modifiers(event: &web_sys::KeyboardEvent) {
+    IS_SHIFT_PRESSED.store(event.shift_key(), Relaxed);
+    IS_CTRL_PRESSED.store(event.ctrl_key(), Relaxed);
+    IS_ALT_PRESSED.store(event.alt_key(), Relaxed);
+    IS_META_PRESSED.store(event.meta_key(), Relaxed);
+}
+
+/// Return true if your program already called [start_recording_keyboard_events()](fn.start_recording_keyboard_events.html) in the past.
+pub fn are_keyboard_events_recorded() -> bool {
+    IS_RECORDING_KEYBOARD_EVENTS.load(Relaxed)
+}
+
+/// Start recording keyboard events in real time.
+/// This cannot be stopped!
+/// It allows you to use [these functions](index.html).
+/// You may want to use [are_keyboard_events_recorded()](fn.are_keyboard_events_recorded.html) to check if your program already called this function in the past.
+pub fn start_recording_keyboard_events() {
+    use wasm_bindgen::{prelude::*, JsCast};
+    use web_sys::window;
+
+    if !are_keyboard_events_recorded() {
+        let window = window().unwrap();
+
+        let event = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+            update_modifiers(&event);
+            let key = Key::from_code(&event.code());
+            if PRESSED_KEYS.lock().unwrap().insert(key) {
+                KEYS_PRESSED_THIS_FRAME.lock().unwrap().insert(key);
+            }
+        }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+        window
+            .add_event_listener_with_callback("keydown", event.as_ref().unchecked_ref())
+            .unwrap();
+        event.forget();
+
+        let event = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+            update_modifiers(&event);
+            PRESSED_KEYS.lock().unwrap().remove(&Key::from_code(&event.code()));
+        }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+        window
+            .add_event_listener_with_callback("keyup", event.as_ref().unchecked_ref())
+            .unwrap();
+        event.forget();
+
+        // keys released while the tab is not focused never fire keyup
+        let event = Closure::wrap(Box::new(move || {
+            PRESSED_KEYS.lock().unwrap().clear();
+            IS_SHIFT_PRESSED.store(false, Relaxed);
+            IS_CTRL_PRESSED.store(false, Relaxed);
+            IS_ALT_PRESSED.store(false, Relaxed);
+            IS_META_PRESSED.store(false, Relaxed);
+        }) as Box<dyn FnMut()>);
+        window
+            .add_event_listener_with_callback("blur", event.as_ref().unchecked_ref())
+            .unwrap();
+        event.forget();
+
+        IS_RECORDING_KEYBOARD_EVENTS.store(true, Relaxed);
+    } else {
+        elog!("Your program is calling start_recording_keyboard_events() multiple times! That's bad!");
+    }
+}
+
+/// Return true if the key is currently pressed.
+/// Make sure you called [start_recording_keyboard_events()](fn.start_recording_keyboard_events.html) before.
+pub fn is_pressed(key: Key) -> bool {
+    PRESSED_KEYS.lock().unwrap().contains(&key)
+}
+
+/// Return every key currently pressed.
+/// Make sure you called [start_recording_keyboard_events()](fn.start_recording_keyboard_events.html) before.
+pub fn get_pressed_keys() -> HashSet<Key> {
+    PRESSED_KEYS.lock().unwrap().clone()
+}
+
+/// Return the keys pressed since the last call of this function.
+/// Call it once per frame to get the keys pressed during the frame, even if they were released before the end of the frame.
+/// Make sure you called [start_recording_keyboard_events()](fn.start_recording_keyboard_events.html) before.
+pub fn take_keys_pressed_this_frame() -> HashSet<Key> {
+    std::mem::take(&mut *KEYS_PRESSED_THIS_FRAME.lock().unwrap())
+}
+
+/// Return true if a shift key is currently pressed.
+/// Make sure you called [start_recording_keyboard_events()](fn.start_recording_keyboard_events.html) before.
+pub fn is_shift_pressed() -> bool {
+    IS_SHIFT_PRESSED.load(Relaxed)
+}
+
+/// Return true if a control key is currently pressed.
+/// Make sure you called [start_recording_keyboard_events()](fn.start_recording_keyboard_events.html) before.
+pub fn is_ctrl_pressed() -> bool {
+    IS_CTRL_PRESSED.load(Relaxed)
+}
+
+/// Return true if an alt key is currently pressed.
+/// Make sure you called [start_recording_keyboard_events()](fn.start_recording_keyboard_events.html) before.
+pub fn is_alt_pressed() -> bool {
+    IS_ALT_PRESSED.load(Relaxed)
+}
+
+/// Return true if a meta key (Windows key, Command key) is currently pressed.
+/// Make sure you called [start_recording_keyboard_events()](fn.start_recording_keyboard_events.html) before.
+pub fn is_meta_pressed() -> bool {
+    IS_META_PRESSED.load(Relaxed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn codes() {
+        assert_eq!(Key::from_code("KeyW"), Key::W);
+        assert_eq!(Key::from_code("Digit7"), Key::Seven);
+        assert_eq!(Key::from_code("Numpad3"), Key::Numpad3);
+        assert_eq!(Key::from_code("F11"), Key::F11);
+        assert_eq!(Key::from_code("Space"), Key::Space);
+        assert_eq!(Key::from_code("ShiftRight"), Key::Shift);
+        assert_eq!(Key::from_code("KeyAB"), Key::Unknow);
+        assert_eq!(Key::from_code("F13"), Key::Unknow);
+        assert_eq!(Key::from_code("Lang1"), Key::Unknow);
+    }
+
+    #[test]
+    fn logical_keys() {
+        assert_eq!(LogicalKey::from("z"), LogicalKey::Character(String::from("z")));
+        assert_eq!(LogicalKey::from("é"), LogicalKey::Character(String::from("é")));
+        assert_eq!(LogicalKey::from(" "), LogicalKey::Character(String::from(" ")));
+        assert_eq!(LogicalKey::from("ArrowUp"), LogicalKey::Named(Key::UpArrow));
+        assert_eq!(LogicalKey::from("F2"), LogicalKey::Named(Key::F2));
+        assert_eq!(LogicalKey::from("Dead"), LogicalKey::Other(String::from("Dead")));
+    }
 }
@@ -1,7 +1,17 @@
-//! This module contains everything related to inputs (keyboard, mouse) and events.
+//! This module contains everything related to inputs (keyboard, text, mouse, touch screens, gamepads, clipboard) and events.
 #![allow(missing_docs)]
 
 pub mod event;
 pub mod mouse;
 pub mod keyboard;
-pub mod joystick;
\ No newline at end of file
+pub mod text;
+pub mod touch;
+pub mod gesture;
+pub mod virtual_gamepad;
+pub mod actions;
+pub mod replay;
+mod serialization;
+pub mod pointer;
+pub mod gamepad;
+pub mod joystick;
+pub mod clipboard;
\ No newline at end of file
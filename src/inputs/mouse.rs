@@ -1,17 +1,21 @@
 use core::convert::TryFrom;
 use crate::elog;
+use crate::graphics::canvas::Canvas;
 
 /// An event related to the mouse
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum MouseEvent {
     /// A click with the main button
-    Click(u32, u32),
-    DoubleClick(u32, u32),
-    Move(u32, u32),
-    Enter(u32, u32),
-    Leave(u32, u32),
-    Up(Button, u32, u32),
-    Down(Button, u32, u32),
+    Click(f64, f64),
+    DoubleClick(f64, f64),
+    Move(f64, f64),
+    /// A movement of the mouse since the last move event, x and y.
+    /// Unlike Move, it is not limited by the edges of the screen: use it with [pointer lock](../../graphics/window/struct.Window.html#method.request_pointer_lock).
+    RelativeMove(f64, f64),
+    Enter(f64, f64),
+    Leave(f64, f64),
+    Up(Button, f64, f64),
+    Down(Button, f64, f64),
     /// Scroll movement x, y and z
     Scroll(f64, f64, f64, DeltaMode),
 }
@@ -20,7 +24,7 @@ use lazy_static::lazy_static;
 use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
 use std::sync::Mutex;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeltaMode {
     Pixel,
     Line,
@@ -41,7 +45,7 @@ impl TryFrom<u32> for DeltaMode {
 }
 
 /// An enum representing a mouse button
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Button {
     /// Main button, usually the left button or the un-initialized state
     Main,
@@ -78,7 +82,7 @@ lazy_static! {
     static ref IS_FOURTH_BUTTON_PRESSED: AtomicBool = AtomicBool::new(false);
     static ref IS_FIFTH_BUTTON_PRESSED: AtomicBool = AtomicBool::new(false);
     static ref IS_MOUSE_IN_CANVAS: AtomicBool = AtomicBool::new(true);
-    static ref MOUSE_POSITION: Mutex<(u32, u32)> = Mutex::new((0,0));
+    static ref MOUSE_POSITION: Mutex<(f64, f64)> = Mutex::new((0.0, 0.0));
 }
 
 /// Return true if your program already called [start_recording_mouse_events()](fn.start_recording_mouse_events.html) in the past.
@@ -150,7 +154,7 @@ pub fn start_recording_mouse_events() {
         event.forget();
 
         let event = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
-            *MOUSE_POSITION.lock().unwrap() = (event.client_x() as u32, event.client_y() as u32);
+            *MOUSE_POSITION.lock().unwrap() = (f64::from(event.client_x()), f64::from(event.client_y()));
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
         window
             .add_event_listener_with_callback("mousemove", event.as_ref().unchecked_ref())
@@ -175,8 +179,25 @@ pub fn is_pressed(button: Button) -> bool {
     }
 }
 
-/// Return the current position of the mouse.
+/// Return the current position of the mouse relative to the viewport.
+/// Negative values are truncated to 0.
 /// Make sure you called [start_recording_mouse_events()](fn.start_recording_mouse_events.html) before.
+#[deprecated(since = "0.7.0", note = "use get_canvas_position() or get_client_position() instead")]
 pub fn get_mouse_position() -> (u32, u32) {
+    let (x, y) = get_client_position();
+    (x as u32, y as u32)
+}
+
+/// Return the current position of the mouse relative to the viewport, in CSS pixels.
+/// Make sure you called [start_recording_mouse_events()](fn.start_recording_mouse_events.html) before.
+pub fn get_client_position() -> (f64, f64) {
     *MOUSE_POSITION.lock().unwrap()
+}
+
+/// Return the current position of the mouse relative to a canvas, in canvas pixels.
+/// The position of the canvas on the page, its CSS scale and the device pixel ratio are taken into account,
+/// like in the coordinates of [MouseEvent](enum.MouseEvent.html)s. Values may be negative if the mouse is outside the canvas.
+/// Make sure you called [start_recording_mouse_events()](fn.start_recording_mouse_events.html) before.
+pub fn get_canvas_position(canvas: &Canvas) -> (f64, f64) {
+    canvas.client_to_canvas_coords(get_client_position())
 }
\ No newline at end of file
@@ -0,0 +1,62 @@
+/// An event related to a pointer.
+/// [Pointer events](https://developer.mozilla.org/en-US/docs/Web/API/Pointer_events) unify mice, pens and touch screens.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerEvent {
+    /// A pointer became active (button pressed, pen or finger touching the screen)
+    Down(Pointer),
+    /// A pointer is no longer active
+    Up(Pointer),
+    /// A pointer moved
+    Move(Pointer),
+    /// The browser interrupted the pointer
+    Cancel(Pointer),
+}
+
+/// The type of device behind a [Pointer](struct.Pointer.html)
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerType {
+    Mouse,
+    Pen,
+    Touch,
+    /// A type unknown by this library
+    Other(String),
+}
+
+impl From<String> for PointerType {
+    fn from(pointer_type: String) -> PointerType {
+        match pointer_type.as_str() {
+            "mouse" => PointerType::Mouse,
+            "pen" => PointerType::Pen,
+            "touch" => PointerType::Touch,
+            _ => PointerType::Other(pointer_type),
+        }
+    }
+}
+
+/// The state of a pointer when an event is fired.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pointer {
+    /// A number identifying the pointer while it is active
+    pub id: i32,
+    /// The type of device
+    pub pointer_type: PointerType,
+    /// True if this is the main pointer (the first finger or the mouse)
+    pub is_primary: bool,
+    /// The position of the pointer, relative to the canvas of the [EventManager](../event/struct.EventManager.html#method.set_canvas) (or to the viewport if it has no canvas)
+    pub coords: (f64, f64),
+    /// The pressure of the pointer, between 0.0 and 1.0.
+    /// Devices without pressure support report 0.5 while a button is pressed and 0.0 otherwise.
+    pub pressure: f32,
+}
+
+impl From<web_sys::PointerEvent> for Pointer {
+    fn from(event: web_sys::PointerEvent) -> Pointer {
+        Pointer {
+            id: event.pointer_id(),
+            pointer_type: PointerType::from(event.pointer_type()),
+            is_primary: event.is_primary(),
+            coords: (f64::from(event.client_x()), f64::from(event.client_y())),
+            pressure: event.pressure(),
+        }
+    }
+}
@@ -0,0 +1,496 @@
+//! Record the events of a game and replay them later.
+//!
+//! A [Recording](struct.Recording.html) contains events and the frame during which they happened.
+//! It can be saved as text and replayed by a [Replay](struct.Replay.html), which can stand in for the [EventManager](../event/struct.EventManager.html)
+//! because both implement [EventSource](trait.EventSource.html).
+//! This is useful to reproduce bugs, to make demos or to test the logic of a game.
+//!
+//! Replays are only deterministic if the game logic only depends on the events and on the frame number (not on the real time).
+//!
+//! # Example
+//!
+//! ```rust
+//! use wasm_game_lib::inputs::replay::{EventSource, Recorder, Recording, Replay};
+//! use wasm_game_lib::inputs::event::Event;
+//! use wasm_game_lib::inputs::mouse::MouseEvent;
+//!
+//! // the game is written for any source of events
+//! fn run_frame(events: &mut impl EventSource, clicks: &mut u32, recorder: &mut Recorder) {
+//!     for event in events.by_ref() {
+//!         recorder.record(&event);
+//!         if let Event::MouseEvent(MouseEvent::Click(_, _)) = event {
+//!             *clicks += 1;
+//!         }
+//!     }
+//!     events.end_frame();
+//!     recorder.end_frame();
+//! }
+//!
+//! let recording: Recording = "0 mouse click 10 20\n2 mouse click 30 40.5\n".parse().unwrap();
+//! let mut replay = Replay::new(recording.clone());
+//! let mut recorder = Recorder::new();
+//! let mut clicks = 0;
+//!
+//! run_frame(&mut replay, &mut clicks, &mut recorder);
+//! run_frame(&mut replay, &mut clicks, &mut recorder);
+//! assert_eq!(clicks, 1);
+//! run_frame(&mut replay, &mut clicks, &mut recorder);
+//! assert_eq!(clicks, 2);
+//! assert!(replay.is_finished());
+//!
+//! // the replayed events have been recorded again
+//! assert_eq!(recorder.into_recording(), recording);
+//! ```
+
+use super::event::{Event, EventManager};
+use super::mouse::{DeltaMode, MouseEvent};
+use super::keyboard::{KeyInfo, KeyboardEvent, LogicalKey};
+use super::gamepad::GamepadEvent;
+use super::clipboard::ClipboardEvent;
+use super::touch::TouchEvent;
+use super::pointer::{PointerEvent, PointerType};
+use super::text::CompositionEvent;
+use super::serialization::{write_string, Tokens};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// A source of events which can be used in the game loop.
+/// It is implemented by the [EventManager](../event/struct.EventManager.html), which records the events of the browser,
+/// and by a [Replay](struct.Replay.html).
+pub trait EventSource: Iterator<Item = Event> {
+    /// Tell the source that a frame ended.
+    /// Call this at the end of every frame, after reading the events.
+    fn end_frame(&mut self) {}
+}
+
+impl EventSource for EventManager {}
+
+/// An event and the frame during which it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// The number of the frame, starting at 0
+    pub frame: u64,
+    /// The event
+    pub event: Event,
+}
+
+/// A list of events and the frames during which they happened.
+///
+/// A recording is serialized as text, with one event per line, preceded by its frame number.
+/// Values are separated by whitespaces and strings are quoted, like the bindings of an [ActionMap](../actions/struct.ActionMap.html).
+/// Use `to_string()` to save it and `parse()` to load it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Recording {
+    /// The events, sorted by frame.
+    /// Parsing a recording fails if the frames are not sorted.
+    pub events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    /// Create an empty recording.
+    pub fn new() -> Recording {
+        Recording::default()
+    }
+}
+
+/// Records the events read by the game.
+/// Give every event to [record()](#method.record) and call [end_frame()](#method.end_frame) at the end of every frame.
+#[derive(Debug, Default)]
+pub struct Recorder {
+    recording: Recording,
+    frame: u64,
+}
+
+impl Recorder {
+    /// Create a recorder starting at frame 0.
+    pub fn new() -> Recorder {
+        Recorder::default()
+    }
+
+    /// Add an event to the recording.
+    pub fn record(&mut self, event: &Event) {
+        self.recording.events.push(RecordedEvent {
+            frame: self.frame,
+            event: event.clone(),
+        });
+    }
+
+    /// Start a new frame.
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Return the number of the current frame.
+    pub fn get_frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Return the events recorded so far.
+    pub fn get_recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Stop recording and return the recording.
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+}
+
+/// Replays a [Recording](struct.Recording.html).
+/// During each frame, iterating over the replay returns the events recorded during the same frame.
+#[derive(Debug)]
+pub struct Replay {
+    recording: Recording,
+    position: usize,
+    frame: u64,
+}
+
+impl Replay {
+    /// Create a replay starting at frame 0.
+    pub fn new(recording: Recording) -> Replay {
+        Replay {
+            recording,
+            position: 0,
+            frame: 0,
+        }
+    }
+
+    /// Return the number of the current frame.
+    pub fn get_frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Return true if every event has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.recording.events.len()
+    }
+}
+
+impl Iterator for Replay {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        let recorded = self.recording.events.get(self.position)?;
+        if recorded.frame > self.frame {
+            return None;
+        }
+        self.position += 1;
+        Some(recorded.event.clone())
+    }
+}
+
+impl EventSource for Replay {
+    fn end_frame(&mut self) {
+        self.frame += 1;
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for recorded in self.events.iter() {
+            write!(f, "{} ", recorded.frame)?;
+            write_event(f, &recorded.event)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Recording, String> {
+        let mut recording = Recording::new();
+        for line in text.lines().map(|line| line.trim()).filter(|line| !line.is_empty()) {
+            let mut tokens = Tokens::new(line)?;
+            let frame = tokens.parse()?;
+            if recording.events.last().is_some_and(|last| last.frame > frame) {
+                return Err(format!("frames are not sorted: {}", line));
+            }
+            let event = parse_event(&mut tokens)?;
+            if tokens.remaining() > 0 {
+                return Err(format!("unexpected values at the end of: {}", line));
+            }
+            recording.events.push(RecordedEvent { frame, event });
+        }
+        Ok(recording)
+    }
+}
+
+fn delta_mode_number(mode: DeltaMode) -> u32 {
+    match mode {
+        DeltaMode::Pixel => 0,
+        DeltaMode::Line => 1,
+        DeltaMode::Page => 2,
+    }
+}
+
+fn pointer_type_name(pointer_type: &PointerType) -> &str {
+    match pointer_type {
+        PointerType::Mouse => "mouse",
+        PointerType::Pen => "pen",
+        PointerType::Touch => "touch",
+        PointerType::Other(name) => name,
+    }
+}
+
+fn write_key_info(f: &mut fmt::Formatter, info: &KeyInfo) -> fmt::Result {
+    write!(f, "{} ", info.code as u32)?;
+    match &info.key {
+        LogicalKey::Character(text) => {
+            write!(f, "char ")?;
+            write_string(f, text)?;
+        },
+        LogicalKey::Named(key) => write!(f, "named {}", *key as u32)?,
+        LogicalKey::Other(name) => {
+            write!(f, "other ")?;
+            write_string(f, name)?;
+        },
+    }
+    let modifiers = &info.modifiers;
+    write!(f, " {} {} {} {} {}", modifiers.shift, modifiers.ctrl, modifiers.alt, modifiers.meta, info.repeat)
+}
+
+fn write_event(f: &mut fmt::Formatter, event: &Event) -> fmt::Result {
+    match event {
+        Event::MouseEvent(event) => match event {
+            MouseEvent::Click(x, y) => write!(f, "mouse click {} {}", x, y),
+            MouseEvent::DoubleClick(x, y) => write!(f, "mouse double_click {} {}", x, y),
+            MouseEvent::Move(x, y) => write!(f, "mouse move {} {}", x, y),
+            MouseEvent::RelativeMove(x, y) => write!(f, "mouse relative_move {} {}", x, y),
+            MouseEvent::Enter(x, y) => write!(f, "mouse enter {} {}", x, y),
+            MouseEvent::Leave(x, y) => write!(f, "mouse leave {} {}", x, y),
+            MouseEvent::Up(button, x, y) => write!(f, "mouse up {} {} {}", *button as i16, x, y),
+            MouseEvent::Down(button, x, y) => write!(f, "mouse down {} {} {}", *button as i16, x, y),
+            MouseEvent::Scroll(x, y, z, mode) => write!(f, "mouse scroll {} {} {} {}", x, y, z, delta_mode_number(*mode)),
+        },
+        Event::KeyboardEvent(KeyboardEvent::Down(info)) => {
+            write!(f, "keyboard down ")?;
+            write_key_info(f, info)
+        },
+        Event::KeyboardEvent(KeyboardEvent::Up(info)) => {
+            write!(f, "keyboard up ")?;
+            write_key_info(f, info)
+        },
+        Event::ResizeEvent(width, height) => write!(f, "resize {} {}", width, height),
+        Event::FocusEvent(focus) => write!(f, "focus {}", focus),
+        Event::GamepadEvent(event) => match event {
+            GamepadEvent::Connected(index, id) => {
+                write!(f, "gamepad connected {} ", index)?;
+                write_string(f, id)
+            },
+            GamepadEvent::Disconnected(index, id) => {
+                write!(f, "gamepad disconnected {} ", index)?;
+                write_string(f, id)
+            },
+            GamepadEvent::ButtonDown(index, button, value) => write!(f, "gamepad button_down {} {} {}", index, button, value),
+            GamepadEvent::ButtonUp(index, button, value) => write!(f, "gamepad button_up {} {} {}", index, button, value),
+            GamepadEvent::AxisMove(index, axis, value) => write!(f, "gamepad axis_move {} {} {}", index, axis, value),
+        },
+        Event::ClipboardEvent(event) => match event {
+            ClipboardEvent::Copy => write!(f, "clipboard copy"),
+            ClipboardEvent::Cut => write!(f, "clipboard cut"),
+            ClipboardEvent::Paste(text) => {
+                write!(f, "clipboard paste ")?;
+                write_string(f, text)
+            },
+        },
+        Event::TouchEvent(event) => {
+            let (name, touches) = match event {
+                TouchEvent::Start(touches) => ("start", touches),
+                TouchEvent::Move(touches) => ("move", touches),
+                TouchEvent::End(touches) => ("end", touches),
+                TouchEvent::Cancel(touches) => ("cancel", touches),
+            };
+            write!(f, "touch {} {}", name, touches.len())?;
+            for touch in touches {
+                write!(f, " {} {} {} {}", touch.identifier, touch.coords.0, touch.coords.1, touch.force)?;
+            }
+            Ok(())
+        },
+        Event::PointerEvent(event) => {
+            let (name, pointer) = match event {
+                PointerEvent::Down(pointer) => ("down", pointer),
+                PointerEvent::Up(pointer) => ("up", pointer),
+                PointerEvent::Move(pointer) => ("move", pointer),
+                PointerEvent::Cancel(pointer) => ("cancel", pointer),
+            };
+            write!(f, "pointer {} {} ", name, pointer.id)?;
+            write_string(f, pointer_type_name(&pointer.pointer_type))?;
+            write!(f, " {} {} {} {}", pointer.is_primary, pointer.coords.0, pointer.coords.1, pointer.pressure)
+        },
+        Event::TextInput(text) => {
+            write!(f, "text ")?;
+            write_string(f, text)
+        },
+        Event::VisibilityChange(visible) => write!(f, "visibility {}", visible),
+        Event::PageHide => write!(f, "page_hide"),
+        Event::BeforeUnload => write!(f, "before_unload"),
+        Event::CompositionEvent(event) => match event {
+            CompositionEvent::Start => write!(f, "composition start"),
+            CompositionEvent::Update(text) => {
+                write!(f, "composition update ")?;
+                write_string(f, text)
+            },
+            CompositionEvent::End(text) => {
+                write!(f, "composition end ")?;
+                write_string(f, text)
+            },
+        },
+    }
+}
+
+fn parse_event(tokens: &mut Tokens) -> Result<Event, String> {
+    let kind = tokens.next_string()?;
+    let event = match kind.as_str() {
+        "resize" => Event::ResizeEvent(tokens.parse()?, tokens.parse()?),
+        "focus" => Event::FocusEvent(tokens.parse()?),
+        "text" => Event::TextInput(tokens.next_string()?),
+        "visibility" => Event::VisibilityChange(tokens.parse()?),
+        "page_hide" => Event::PageHide,
+        "before_unload" => Event::BeforeUnload,
+        _ => {
+            let name = tokens.next_string()?;
+            match (kind.as_str(), name.as_str()) {
+                ("mouse", "click") => {
+                    let (x, y) = tokens.parse_coords()?;
+                    Event::MouseEvent(MouseEvent::Click(x, y))
+                },
+                ("mouse", "double_click") => {
+                    let (x, y) = tokens.parse_coords()?;
+                    Event::MouseEvent(MouseEvent::DoubleClick(x, y))
+                },
+                ("mouse", "move") => {
+                    let (x, y) = tokens.parse_coords()?;
+                    Event::MouseEvent(MouseEvent::Move(x, y))
+                },
+                ("mouse", "relative_move") => {
+                    let (x, y) = tokens.parse_coords()?;
+                    Event::MouseEvent(MouseEvent::RelativeMove(x, y))
+                },
+                ("mouse", "enter") => {
+                    let (x, y) = tokens.parse_coords()?;
+                    Event::MouseEvent(MouseEvent::Enter(x, y))
+                },
+                ("mouse", "leave") => {
+                    let (x, y) = tokens.parse_coords()?;
+                    Event::MouseEvent(MouseEvent::Leave(x, y))
+                },
+                ("mouse", "up") => {
+                    let button = tokens.parse_button()?;
+                    let (x, y) = tokens.parse_coords()?;
+                    Event::MouseEvent(MouseEvent::Up(button, x, y))
+                },
+                ("mouse", "down") => {
+                    let button = tokens.parse_button()?;
+                    let (x, y) = tokens.parse_coords()?;
+                    Event::MouseEvent(MouseEvent::Down(button, x, y))
+                },
+                ("mouse", "scroll") => {
+                    let (x, y, z) = (tokens.parse()?, tokens.parse()?, tokens.parse()?);
+                    let mode = DeltaMode::try_from(tokens.parse::<u32>()?).map_err(|n| format!("invalid delta mode: {}", n))?;
+                    Event::MouseEvent(MouseEvent::Scroll(x, y, z, mode))
+                },
+                ("keyboard", "down") => Event::KeyboardEvent(KeyboardEvent::Down(tokens.parse_key_info()?)),
+                ("keyboard", "up") => Event::KeyboardEvent(KeyboardEvent::Up(tokens.parse_key_info()?)),
+                ("gamepad", "connected") => Event::GamepadEvent(GamepadEvent::Connected(tokens.parse()?, tokens.next_string()?)),
+                ("gamepad", "disconnected") => Event::GamepadEvent(GamepadEvent::Disconnected(tokens.parse()?, tokens.next_string()?)),
+                ("gamepad", "button_down") => Event::GamepadEvent(GamepadEvent::ButtonDown(tokens.parse()?, tokens.parse()?, tokens.parse()?)),
+                ("gamepad", "button_up") => Event::GamepadEvent(GamepadEvent::ButtonUp(tokens.parse()?, tokens.parse()?, tokens.parse()?)),
+                ("gamepad", "axis_move") => Event::GamepadEvent(GamepadEvent::AxisMove(tokens.parse()?, tokens.parse()?, tokens.parse()?)),
+                ("clipboard", "copy") => Event::ClipboardEvent(ClipboardEvent::Copy),
+                ("clipboard", "cut") => Event::ClipboardEvent(ClipboardEvent::Cut),
+                ("clipboard", "paste") => Event::ClipboardEvent(ClipboardEvent::Paste(tokens.next_string()?)),
+                ("touch", "start") => Event::TouchEvent(TouchEvent::Start(tokens.parse_touches()?)),
+                ("touch", "move") => Event::TouchEvent(TouchEvent::Move(tokens.parse_touches()?)),
+                ("touch", "end") => Event::TouchEvent(TouchEvent::End(tokens.parse_touches()?)),
+                ("touch", "cancel") => Event::TouchEvent(TouchEvent::Cancel(tokens.parse_touches()?)),
+                ("pointer", "down") => Event::PointerEvent(PointerEvent::Down(tokens.parse_pointer()?)),
+                ("pointer", "up") => Event::PointerEvent(PointerEvent::Up(tokens.parse_pointer()?)),
+                ("pointer", "move") => Event::PointerEvent(PointerEvent::Move(tokens.parse_pointer()?)),
+                ("pointer", "cancel") => Event::PointerEvent(PointerEvent::Cancel(tokens.parse_pointer()?)),
+                ("composition", "start") => Event::CompositionEvent(CompositionEvent::Start),
+                ("composition", "update") => Event::CompositionEvent(CompositionEvent::Update(tokens.next_string()?)),
+                ("composition", "end") => Event::CompositionEvent(CompositionEvent::End(tokens.next_string()?)),
+                _ => return Err(format!("invalid event: {} {}", kind, name)),
+            }
+        },
+    };
+    Ok(event)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::inputs::mouse::Button;
+    use crate::inputs::keyboard::{Key, Modifiers};
+    use crate::inputs::touch::Touch;
+    use crate::inputs::pointer::Pointer;
+
+    #[test]
+    fn serialization() {
+        let events = vec![
+            Event::MouseEvent(MouseEvent::Down(Button::Secondary, -3.5, 12.25)),
+            Event::MouseEvent(MouseEvent::Scroll(0.0, -120.0, 0.0, DeltaMode::Line)),
+            Event::MouseEvent(MouseEvent::RelativeMove(1.0, -2.0)),
+            Event::KeyboardEvent(KeyboardEvent::Down(KeyInfo {
+                code: Key::Q,
+                key: LogicalKey::Character(String::from("a")),
+                modifiers: Modifiers { shift: false, ctrl: true, alt: false, meta: false },
+                repeat: true,
+            })),
+            Event::KeyboardEvent(KeyboardEvent::Up(KeyInfo::from(Key::Escape))),
+            Event::ResizeEvent(800, 600),
+            Event::FocusEvent(false),
+            Event::GamepadEvent(GamepadEvent::Connected(0, String::from("Xbox \"360\" (STANDARD GAMEPAD)"))),
+            Event::GamepadEvent(GamepadEvent::AxisMove(0, 1, -0.75)),
+            Event::ClipboardEvent(ClipboardEvent::Paste(String::from("two\nlines\t\\"))),
+            Event::TouchEvent(TouchEvent::Move(vec![
+                Touch { identifier: 1, coords: (1.5, 2.5), force: 0.5 },
+                Touch { identifier: 2, coords: (3.0, 4.0), force: 0.0 },
+            ])),
+            Event::PointerEvent(PointerEvent::Down(Pointer {
+                id: 7,
+                pointer_type: PointerType::Other(String::from("eye tracker")),
+                is_primary: true,
+                coords: (0.1, 0.2),
+                pressure: 0.3,
+            })),
+            Event::TextInput(String::from("日本語 ")),
+            Event::CompositionEvent(CompositionEvent::Update(String::from("にほ"))),
+            Event::VisibilityChange(false),
+            Event::PageHide,
+        ];
+        let recording = Recording {
+            events: events.into_iter().enumerate().map(|(frame, event)| RecordedEvent { frame: frame as u64 / 3, event }).collect(),
+        };
+
+        let text = recording.to_string();
+        assert_eq!(text.lines().count(), recording.events.len());
+        assert_eq!(text.parse::<Recording>().unwrap(), recording);
+
+        assert!("0 mouse click 1".parse::<Recording>().is_err());
+        assert!("0 mouse click 1 2 3".parse::<Recording>().is_err());
+        assert!("0 text \"unterminated".parse::<Recording>().is_err());
+        // a replay could never reach the events of an earlier frame
+        assert!("2 focus true\n1 focus false".parse::<Recording>().is_err());
+    }
+
+    #[test]
+    fn replay() {
+        let recording: Recording = "1 focus true\n1 focus false\n3 resize 10 20\n".parse().unwrap();
+        let mut replay = Replay::new(recording);
+
+        assert_eq!(replay.next(), None);
+        replay.end_frame();
+        assert_eq!(replay.next(), Some(Event::FocusEvent(true)));
+        assert_eq!(replay.next(), Some(Event::FocusEvent(false)));
+        assert_eq!(replay.next(), None);
+        replay.end_frame();
+        replay.end_frame();
+        assert!(!replay.is_finished());
+        assert_eq!(replay.next(), Some(Event::ResizeEvent(10, 20)));
+        assert!(replay.is_finished());
+    }
+}
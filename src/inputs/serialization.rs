@@ -0,0 +1,194 @@
+//! The text format shared by the serialization of [ActionMap](../actions/struct.ActionMap.html) bindings and [Recording](../replay/struct.Recording.html)s.
+//! Every line is a list of values separated by whitespaces.
+//! Strings are quoted, and `"`, `\`, newlines and tabs are escaped with a backslash.
+
+use super::mouse::Button;
+use super::keyboard::{Key, KeyInfo, LogicalKey, Modifiers};
+use super::touch::Touch;
+use super::pointer::{Pointer, PointerType};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Write a quoted and escaped string.
+pub(crate) fn write_string(f: &mut fmt::Formatter, text: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// The values of a line.
+pub(crate) struct Tokens {
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl Tokens {
+    pub(crate) fn new(line: &str) -> Result<Tokens, String> {
+        let mut tokens = Vec::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let mut token = String::new();
+            if c == '"' {
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => token.push('\n'),
+                            Some('r') => token.push('\r'),
+                            Some('t') => token.push('\t'),
+                            Some(c @ '"') | Some(c @ '\\') => token.push(c),
+                            _ => return Err(format!("invalid escape sequence in: {}", line)),
+                        },
+                        Some(c) => token.push(c),
+                        None => return Err(format!("unterminated string in: {}", line)),
+                    }
+                }
+            } else {
+                token.push(c);
+                while let Some(c) = chars.peek() {
+                    if c.is_whitespace() {
+                        break;
+                    }
+                    token.push(*c);
+                    chars.next();
+                }
+            }
+            tokens.push(token);
+        }
+        Ok(Tokens { tokens, position: 0 })
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.tokens.len() - self.position
+    }
+
+    pub(crate) fn next_string(&mut self) -> Result<String, String> {
+        let token = self.tokens.get(self.position).cloned().ok_or_else(|| String::from("missing value"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    pub(crate) fn parse<T: FromStr>(&mut self) -> Result<T, String> {
+        let token = self.next_string()?;
+        token.parse().map_err(|_| format!("invalid value: {}", token))
+    }
+
+    pub(crate) fn parse_coords(&mut self) -> Result<(f64, f64), String> {
+        Ok((self.parse()?, self.parse()?))
+    }
+
+    pub(crate) fn parse_button(&mut self) -> Result<Button, String> {
+        let number: i16 = self.parse()?;
+        Button::try_from(number).map_err(|n| format!("invalid mouse button: {}", n))
+    }
+
+    pub(crate) fn parse_key(&mut self) -> Result<Key, String> {
+        match Key::from(self.parse::<u32>()?) {
+            Key::Unknow => Err(String::from("invalid key")),
+            key => Ok(key),
+        }
+    }
+
+    pub(crate) fn parse_key_info(&mut self) -> Result<KeyInfo, String> {
+        let code = Key::from(self.parse::<u32>()?);
+        let key = match self.next_string()?.as_str() {
+            "char" => LogicalKey::Character(self.next_string()?),
+            "named" => LogicalKey::Named(self.parse_key()?),
+            "other" => LogicalKey::Other(self.next_string()?),
+            kind => return Err(format!("invalid logical key: {}", kind)),
+        };
+        Ok(KeyInfo {
+            code,
+            key,
+            modifiers: Modifiers {
+                shift: self.parse()?,
+                ctrl: self.parse()?,
+                alt: self.parse()?,
+                meta: self.parse()?,
+            },
+            repeat: self.parse()?,
+        })
+    }
+
+    pub(crate) fn parse_touches(&mut self) -> Result<Vec<Touch>, String> {
+        let count: usize = self.parse()?;
+        let mut touches = Vec::new();
+        for _ in 0..count {
+            touches.push(Touch {
+                identifier: self.parse()?,
+                coords: self.parse_coords()?,
+                force: self.parse()?,
+            });
+        }
+        Ok(touches)
+    }
+
+    pub(crate) fn parse_pointer(&mut self) -> Result<Pointer, String> {
+        Ok(Pointer {
+            id: self.parse()?,
+            pointer_type: PointerType::from(self.next_string()?),
+            is_primary: self.parse()?,
+            coords: self.parse_coords()?,
+            pressure: self.parse()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::inputs::actions::{ActionMap, Input};
+    use crate::inputs::keyboard::Key;
+
+    struct Quoted<'a>(&'a str);
+
+    impl fmt::Display for Quoted<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write_string(f, self.0)
+        }
+    }
+
+    #[test]
+    fn strings() {
+        let text = "say \"hi\"\n\t\\ 日本";
+        let line = format!("bare {} 3", Quoted(text));
+        let mut tokens = Tokens::new(&line).unwrap();
+        assert_eq!(tokens.remaining(), 3);
+        assert_eq!(tokens.next_string().unwrap(), "bare");
+        assert_eq!(tokens.next_string().unwrap(), text);
+        assert_eq!(tokens.parse::<u32>().unwrap(), 3);
+        assert!(tokens.next_string().is_err());
+
+        assert!(Tokens::new("\"unterminated").is_err());
+        assert!(Tokens::new("\"invalid \\x escape\"").is_err());
+    }
+
+    #[test]
+    fn action_names() {
+        let mut actions = ActionMap::new();
+        actions.bind("open \"the\" door", Input::Key(Key::E));
+
+        let text = actions.to_string();
+        assert_eq!(text, format!("action \"open \\\"the\\\" door\" key:{}\n", Key::E as u32));
+        let restored: ActionMap = text.parse().unwrap();
+        assert_eq!(restored.get_bindings("open \"the\" door"), &[Input::Key(Key::E)]);
+
+        // unquoted names are still accepted
+        let restored: ActionMap = format!("action jump key:{}", Key::Space as u32).parse().unwrap();
+        assert_eq!(restored.get_bindings("jump"), &[Input::Key(Key::Space)]);
+        assert!("action jump".parse::<ActionMap>().is_err());
+    }
+}
@@ -0,0 +1,42 @@
+//! Text input, including the composition of characters with an [IME](https://en.wikipedia.org/wiki/Input_method).
+//!
+//! Keyboard events tell which keys are pressed, not which text is typed.
+//! To receive text, start recording text input events with the [EventManager](../event/struct.EventManager.html)
+//! and call [focus_text_input()](../event/struct.EventManager.html#method.focus_text_input) when the player starts typing (in a chat box, a name entry screen...).
+//!
+//! Text is received as [TextInput](../event/enum.Event.html#variant.TextInput) events.
+//! While an IME is composing characters (Japanese, Chinese, Korean...), [CompositionEvent](enum.CompositionEvent.html)s let you display the text being composed.
+//!
+//! Text is read from the `input` event of a hidden element rather than from `keypress`.
+//! `keypress` is deprecated, is not fired by most virtual keyboards on mobile devices and is not fired for the text produced by an IME.
+//! Listening to both would report every character twice.
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+
+/// An event fired while the user is composing characters with an IME.
+/// The composed text is also sent as a [TextInput](../event/enum.Event.html#variant.TextInput) event when the composition ends.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompositionEvent {
+    /// The user started composing characters
+    Start,
+    /// The text being composed changed, contains the whole text being composed
+    Update(String),
+    /// The user validated or cancelled the composition, contains the composed text
+    End(String),
+}
+
+/// Create the invisible element receiving the text typed by the user.
+pub(crate) fn create_hidden_input() -> HtmlInputElement {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let input = document.create_element("input").unwrap().dyn_into::<HtmlInputElement>().unwrap();
+    input.set_type("text");
+    input.set_attribute("style", "position: fixed; left: 0; top: 0; width: 1px; height: 1px; padding: 0; border: 0; opacity: 0; pointer-events: none;").unwrap();
+    input.set_attribute("autocomplete", "off").unwrap();
+    input.set_attribute("autocapitalize", "off").unwrap();
+    input.set_attribute("autocorrect", "off").unwrap();
+    input.set_attribute("spellcheck", "false").unwrap();
+    input.set_attribute("aria-hidden", "true").unwrap();
+    document.body().unwrap().append_child(&input).unwrap();
+    input
+}
@@ -0,0 +1,43 @@
+/// An event related to touch screens.
+/// Every variant contains the touches which changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TouchEvent {
+    /// Fingers started touching the screen
+    Start(Vec<Touch>),
+    /// Fingers moved on the screen
+    Move(Vec<Touch>),
+    /// Fingers stopped touching the screen
+    End(Vec<Touch>),
+    /// Touches were interrupted (too many fingers, the browser took over the gesture...)
+    Cancel(Vec<Touch>),
+}
+
+/// A single point of contact with a touch screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    /// A number identifying the finger during the whole touch, from Start to End
+    pub identifier: i32,
+    /// The position of the touch, relative to the canvas of the [EventManager](../event/struct.EventManager.html#method.set_canvas) (or to the viewport if it has no canvas)
+    pub coords: (f64, f64),
+    /// The pressure of the touch, between 0.0 and 1.0 (0.0 if the device does not support it)
+    pub force: f32,
+}
+
+impl From<web_sys::Touch> for Touch {
+    fn from(touch: web_sys::Touch) -> Touch {
+        Touch {
+            identifier: touch.identifier(),
+            coords: (f64::from(touch.client_x()), f64::from(touch.client_y())),
+            force: touch.force(),
+        }
+    }
+}
+
+/// Return the touches which changed during a web_sys touch event.
+pub(crate) fn changed_touches(event: &web_sys::TouchEvent) -> Vec<Touch> {
+    let list = event.changed_touches();
+    (0..list.length())
+        .filter_map(|idx| list.get(idx))
+        .map(Touch::from)
+        .collect()
+}
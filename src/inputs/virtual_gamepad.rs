@@ -0,0 +1,490 @@
+use super::touch::{Touch, TouchEvent};
+use super::gamepad::{self, ButtonState, GamepadAxis, GamepadButton, GamepadState};
+use super::event::Event;
+use crate::graphics::drawable::Drawable;
+use crate::graphics::canvas::Canvas;
+use crate::graphics::color::Color;
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicU32, Ordering::Relaxed};
+
+/// The index of the gamepad slot used by the first [VirtualGamepad](struct.VirtualGamepad.html).
+/// The next virtual gamepads use the indices below it.
+/// They are chosen so that they never collide with a real gamepad.
+pub const VIRTUAL_GAMEPAD_INDEX: u32 = u32::MAX;
+
+/// The id reported in the [GamepadState](../gamepad/struct.GamepadState.html) of a [VirtualGamepad](struct.VirtualGamepad.html).
+pub const VIRTUAL_GAMEPAD_ID: &str = "Virtual gamepad (STANDARD GAMEPAD)";
+
+static NEXT_VIRTUAL_GAMEPAD_INDEX: AtomicU32 = AtomicU32::new(VIRTUAL_GAMEPAD_INDEX);
+
+fn draw_circle(canvas: &mut Canvas, center: (f64, f64), radius: f64, color: &Color) {
+    canvas.context.begin_path();
+    canvas.context.arc(center.0, center.1, radius, 0.0, 2.0 * PI).unwrap();
+    canvas.context.set_fill_style_str(&color.to_string());
+    canvas.context.fill();
+}
+
+fn is_inside(coords: (f64, f64), center: (f64, f64), radius: f64) -> bool {
+    (coords.0 - center.0).hypot(coords.1 - center.1) <= radius
+}
+
+/// An on-screen joystick for touch screens.
+/// A finger starting inside the joystick moves the knob until it is released.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::inputs::virtual_gamepad::VirtualJoystick;
+/// use wasm_game_lib::inputs::touch::{TouchEvent, Touch};
+///
+/// let mut joystick = VirtualJoystick::new((100.0, 300.0), 50.0);
+/// joystick.handle_touch_event(&TouchEvent::Start(vec![Touch { identifier: 3, coords: (100.0, 300.0), force: 0.0 }]));
+/// joystick.handle_touch_event(&TouchEvent::Move(vec![Touch { identifier: 3, coords: (125.0, 300.0), force: 0.0 }]));
+/// assert_eq!(joystick.get_axes(), (0.5, 0.0));
+/// ```
+#[derive(Debug)]
+pub struct VirtualJoystick {
+    /// The center of the joystick on the canvas
+    pub center: (f64, f64),
+    /// The radius of the area in which the knob can move
+    pub radius: f64,
+    /// The radius of the knob
+    pub knob_radius: f64,
+    /// The color of the area in which the knob can move
+    pub base_color: Color,
+    /// The color of the knob
+    pub knob_color: Color,
+    touch: Option<i32>,
+    axes: (f64, f64),
+}
+
+impl VirtualJoystick {
+    /// Create a joystick with default colors.
+    pub fn new(center: (f64, f64), radius: f64) -> VirtualJoystick {
+        VirtualJoystick {
+            center,
+            radius,
+            knob_radius: radius / 2.0,
+            base_color: Color::new_with_alpha(128, 128, 128, 80),
+            knob_color: Color::new_with_alpha(255, 255, 255, 160),
+            touch: None,
+            axes: (0.0, 0.0),
+        }
+    }
+
+    /// Update the joystick with a touch event.
+    pub fn handle_touch_event(&mut self, event: &TouchEvent) {
+        match event {
+            TouchEvent::Start(touches) => for touch in touches {
+                self.touch_start(touch);
+            },
+            TouchEvent::Move(touches) => for touch in touches {
+                self.touch_move(touch);
+            },
+            TouchEvent::End(touches) | TouchEvent::Cancel(touches) => for touch in touches {
+                self.touch_end(touch);
+            },
+        }
+    }
+
+    /// Return the position of the knob.
+    /// Both values are between -1.0 and 1.0, like the axes of a real gamepad.
+    pub fn get_axes(&self) -> (f64, f64) {
+        self.axes
+    }
+
+    /// Return true if a finger is currently moving the knob.
+    pub fn is_active(&self) -> bool {
+        self.touch.is_some()
+    }
+
+    /// Return true if the touch has been captured by the joystick.
+    fn touch_start(&mut self, touch: &Touch) -> bool {
+        if self.touch.is_none() && is_inside(touch.coords, self.center, self.radius) {
+            self.touch = Some(touch.identifier);
+            self.touch_move(touch)
+        } else {
+            false
+        }
+    }
+
+    fn touch_move(&mut self, touch: &Touch) -> bool {
+        if self.touch != Some(touch.identifier) || self.radius <= 0.0 {
+            return false;
+        }
+        let mut axes = ((touch.coords.0 - self.center.0) / self.radius, (touch.coords.1 - self.center.1) / self.radius);
+        let length = axes.0.hypot(axes.1);
+        if length > 1.0 {
+            axes = (axes.0 / length, axes.1 / length);
+        }
+        self.axes = axes;
+        true
+    }
+
+    fn touch_end(&mut self, touch: &Touch) -> bool {
+        if self.touch != Some(touch.identifier) {
+            return false;
+        }
+        self.touch = None;
+        self.axes = (0.0, 0.0);
+        true
+    }
+}
+
+impl Drawable for VirtualJoystick {
+    fn draw_on_canvas(&self, canvas: &mut Canvas) {
+        draw_circle(canvas, self.center, self.radius, &self.base_color);
+        let knob = (self.center.0 + self.axes.0 * self.radius, self.center.1 + self.axes.1 * self.radius);
+        draw_circle(canvas, knob, self.knob_radius, &self.knob_color);
+    }
+}
+
+/// An on-screen button for touch screens.
+/// The button is pressed while a finger which started inside it stays on the screen.
+#[derive(Debug)]
+pub struct VirtualButton {
+    /// The center of the button on the canvas
+    pub center: (f64, f64),
+    /// The radius of the button
+    pub radius: f64,
+    /// The color of the button when it is released
+    pub color: Color,
+    /// The color of the button when it is pressed
+    pub pressed_color: Color,
+    touch: Option<i32>,
+}
+
+impl VirtualButton {
+    /// Create a button with default colors.
+    pub fn new(center: (f64, f64), radius: f64) -> VirtualButton {
+        VirtualButton {
+            center,
+            radius,
+            color: Color::new_with_alpha(128, 128, 128, 80),
+            pressed_color: Color::new_with_alpha(255, 255, 255, 160),
+            touch: None,
+        }
+    }
+
+    /// Update the button with a touch event.
+    pub fn handle_touch_event(&mut self, event: &TouchEvent) {
+        match event {
+            TouchEvent::Start(touches) => for touch in touches {
+                self.touch_start(touch);
+            },
+            TouchEvent::Move(_) => (),
+            TouchEvent::End(touches) | TouchEvent::Cancel(touches) => for touch in touches {
+                self.touch_end(touch);
+            },
+        }
+    }
+
+    /// Return true if the button is pressed.
+    pub fn is_pressed(&self) -> bool {
+        self.touch.is_some()
+    }
+
+    fn touch_start(&mut self, touch: &Touch) -> bool {
+        if self.touch.is_none() && is_inside(touch.coords, self.center, self.radius) {
+            self.touch = Some(touch.identifier);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn touch_end(&mut self, touch: &Touch) -> bool {
+        if self.touch != Some(touch.identifier) {
+            return false;
+        }
+        self.touch = None;
+        true
+    }
+}
+
+impl Drawable for VirtualButton {
+    fn draw_on_canvas(&self, canvas: &mut Canvas) {
+        let color = if self.is_pressed() { &self.pressed_color } else { &self.color };
+        draw_circle(canvas, self.center, self.radius, color);
+    }
+}
+
+/// A set of on-screen controls behaving like a real gamepad.
+/// While it exists, it is plugged in its own gamepad slot (see [get_index()](#method.get_index)) using the standard mapping:
+/// it is returned by [gamepad::state()](../gamepad/fn.state.html) and produces [GamepadEvent](../gamepad/enum.GamepadEvent.html)s,
+/// so the code handling real gamepads can be used with touch screens unchanged.
+/// It is unplugged when dropped.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::inputs::virtual_gamepad::{VirtualGamepad, VirtualJoystick, VirtualButton};
+/// use wasm_game_lib::inputs::gamepad::{GamepadButton, GamepadAxis, GamepadState};
+/// use wasm_game_lib::inputs::touch::{TouchEvent, Touch};
+///
+/// // the same function is used for real and virtual gamepads
+/// fn wants_to_jump(state: &GamepadState) -> bool {
+///     state.button(GamepadButton::South).pressed
+/// }
+///
+/// let mut gamepad = VirtualGamepad::new(VirtualJoystick::new((100.0, 300.0), 50.0));
+/// gamepad.add_button(GamepadButton::South, VirtualButton::new((500.0, 300.0), 30.0));
+///
+/// // in the game loop, give every touch event to the gamepad
+/// gamepad.handle_touch_event(&TouchEvent::Start(vec![Touch { identifier: 0, coords: (510.0, 290.0), force: 0.0 }]));
+///
+/// assert!(wants_to_jump(&gamepad.state()));
+/// assert_eq!(gamepad.state().axis(GamepadAxis::LeftStickX), 0.0);
+/// ```
+#[derive(Debug)]
+pub struct VirtualGamepad {
+    index: u32,
+    joystick: VirtualJoystick,
+    buttons: Vec<(GamepadButton, VirtualButton)>,
+}
+
+impl VirtualGamepad {
+    /// Create a virtual gamepad without any button and plug it in a new gamepad slot.
+    pub fn new(joystick: VirtualJoystick) -> VirtualGamepad {
+        let gamepad = VirtualGamepad {
+            index: NEXT_VIRTUAL_GAMEPAD_INDEX.fetch_sub(1, Relaxed),
+            joystick,
+            buttons: Vec::new(),
+        };
+        gamepad.sync();
+        gamepad
+    }
+
+    /// Return the index of the gamepad slot of this virtual gamepad.
+    /// Use it with [gamepad::state()](../gamepad/fn.state.html) or to recognize its [GamepadEvent](../gamepad/enum.GamepadEvent.html)s.
+    pub fn get_index(&self) -> u32 {
+        self.index
+    }
+
+    /// Return the joystick, reported as the left stick.
+    pub fn get_joystick(&self) -> &VirtualJoystick {
+        &self.joystick
+    }
+
+    /// Replace the joystick.
+    pub fn set_joystick(&mut self, joystick: VirtualJoystick) {
+        self.joystick = joystick;
+        self.sync();
+    }
+
+    /// Move the joystick, for example when the canvas is resized.
+    pub fn set_joystick_center(&mut self, center: (f64, f64)) {
+        self.joystick.center = center;
+        self.sync();
+    }
+
+    /// Return the buttons and the gamepad buttons they emulate.
+    pub fn get_buttons(&self) -> &[(GamepadButton, VirtualButton)] {
+        &self.buttons
+    }
+
+    /// Add a button emulating a gamepad button.
+    pub fn add_button(&mut self, button: GamepadButton, widget: VirtualButton) {
+        self.buttons.push((button, widget));
+        self.sync();
+    }
+
+    /// Remove the buttons emulating a gamepad button.
+    pub fn remove_button(&mut self, button: GamepadButton) {
+        self.buttons.retain(|(gamepad_button, _)| *gamepad_button != button);
+        self.sync();
+    }
+
+    /// Move the buttons emulating a gamepad button, for example when the canvas is resized.
+    pub fn set_button_center(&mut self, button: GamepadButton, center: (f64, f64)) {
+        for (gamepad_button, widget) in self.buttons.iter_mut() {
+            if *gamepad_button == button {
+                widget.center = center;
+            }
+        }
+        self.sync();
+    }
+
+    /// Publish the state of the controls in the gamepad slot.
+    fn sync(&self) {
+        gamepad::set_virtual_gamepad(self.state());
+    }
+
+    /// Update the controls with an event from the [EventManager](../event/struct.EventManager.html).
+    /// Events which are not touch events are ignored.
+    pub fn handle_event(&mut self, event: &Event) {
+        if let Event::TouchEvent(event) = event {
+            self.handle_touch_event(event);
+        }
+    }
+
+    /// Update the controls with a touch event.
+    /// Each finger is captured by one control at most.
+    pub fn handle_touch_event(&mut self, event: &TouchEvent) {
+        match event {
+            TouchEvent::Start(touches) => {
+                for touch in touches {
+                    if self.joystick.touch_start(touch) {
+                        continue;
+                    }
+                    for (_, button) in self.buttons.iter_mut() {
+                        if button.touch_start(touch) {
+                            break;
+                        }
+                    }
+                }
+            },
+            TouchEvent::Move(_) => self.joystick.handle_touch_event(event),
+            TouchEvent::End(_) | TouchEvent::Cancel(_) => {
+                self.joystick.handle_touch_event(event);
+                for (_, button) in self.buttons.iter_mut() {
+                    button.handle_touch_event(event);
+                }
+            },
+        }
+        self.sync();
+    }
+
+    /// Return the state of the controls, like a real gamepad using the standard mapping.
+    pub fn state(&self) -> GamepadState {
+        let mut buttons = vec![ButtonState::default(); GamepadButton::ALL.len()];
+        for (gamepad_button, button) in self.buttons.iter() {
+            if button.is_pressed() {
+                buttons[gamepad_button.standard_index()] = ButtonState { pressed: true, value: 1.0 };
+            }
+        }
+
+        let mut axes = vec![0.0; GamepadAxis::ALL.len()];
+        axes[GamepadAxis::LeftStickX.standard_index()] = self.joystick.axes.0;
+        axes[GamepadAxis::LeftStickY.standard_index()] = self.joystick.axes.1;
+
+        GamepadState {
+            index: self.index,
+            id: String::from(VIRTUAL_GAMEPAD_ID),
+            standard_mapping: true,
+            mapping: gamepad::get_mapping(VIRTUAL_GAMEPAD_ID, true),
+            buttons,
+            axes,
+        }
+    }
+}
+
+impl Drop for VirtualGamepad {
+    fn drop(&mut self) {
+        gamepad::remove_virtual_gamepad(self.index);
+    }
+}
+
+impl Drawable for VirtualGamepad {
+    fn draw_on_canvas(&self, canvas: &mut Canvas) {
+        self.joystick.draw_on_canvas(canvas);
+        for (_, button) in self.buttons.iter() {
+            button.draw_on_canvas(canvas);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn touch(identifier: i32, coords: (f64, f64)) -> Vec<Touch> {
+        vec![Touch { identifier, coords, force: 0.0 }]
+    }
+
+    #[test]
+    fn joystick_axes() {
+        let mut joystick = VirtualJoystick::new((100.0, 100.0), 50.0);
+
+        // a finger starting outside is ignored
+        joystick.handle_touch_event(&TouchEvent::Start(touch(0, (200.0, 100.0))));
+        assert!(!joystick.is_active());
+        joystick.handle_touch_event(&TouchEvent::Move(touch(0, (125.0, 100.0))));
+        assert_eq!(joystick.get_axes(), (0.0, 0.0));
+
+        joystick.handle_touch_event(&TouchEvent::Start(touch(1, (100.0, 100.0))));
+        assert!(joystick.is_active());
+        joystick.handle_touch_event(&TouchEvent::Move(touch(1, (100.0, 75.0))));
+        assert_eq!(joystick.get_axes(), (0.0, -0.5));
+
+        // the knob stays in the circle
+        joystick.handle_touch_event(&TouchEvent::Move(touch(1, (400.0, 100.0))));
+        assert_eq!(joystick.get_axes(), (1.0, 0.0));
+        joystick.handle_touch_event(&TouchEvent::Move(touch(1, (200.0, 200.0))));
+        let axes = joystick.get_axes();
+        assert!((axes.0 - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-9);
+        assert!((axes.1 - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-9);
+
+        // only the finger moving the knob releases it
+        joystick.handle_touch_event(&TouchEvent::End(touch(0, (125.0, 100.0))));
+        assert!(joystick.is_active());
+        joystick.handle_touch_event(&TouchEvent::End(touch(1, (200.0, 200.0))));
+        assert!(!joystick.is_active());
+        assert_eq!(joystick.get_axes(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn button_hit_testing() {
+        let mut button = VirtualButton::new((50.0, 50.0), 10.0);
+
+        button.handle_touch_event(&TouchEvent::Start(touch(0, (61.0, 50.0))));
+        assert!(!button.is_pressed());
+        button.handle_touch_event(&TouchEvent::Start(touch(1, (56.0, 58.0))));
+        assert!(button.is_pressed());
+
+        // moving out of the button does not release it
+        button.handle_touch_event(&TouchEvent::Move(touch(1, (100.0, 100.0))));
+        assert!(button.is_pressed());
+        button.handle_touch_event(&TouchEvent::Cancel(touch(1, (100.0, 100.0))));
+        assert!(!button.is_pressed());
+    }
+
+    #[test]
+    fn gamepad_slot() {
+        let mut gamepad = VirtualGamepad::new(VirtualJoystick::new((100.0, 100.0), 50.0));
+        gamepad.add_button(GamepadButton::South, VirtualButton::new((100.0, 100.0), 80.0));
+        gamepad.add_button(GamepadButton::East, VirtualButton::new((300.0, 100.0), 20.0));
+
+        // the joystick captures the finger before the overlapping button
+        gamepad.handle_touch_event(&TouchEvent::Start(touch(0, (125.0, 100.0))));
+        gamepad.handle_touch_event(&TouchEvent::Start(touch(1, (300.0, 110.0))));
+
+        let index = gamepad.get_index();
+        let state = gamepad::virtual_gamepads().into_iter().find(|state| state.index == index).unwrap();
+        assert_eq!(state, gamepad.state());
+        assert_eq!(state.axis(GamepadAxis::LeftStickX), 0.5);
+        assert!(!state.button(GamepadButton::South).pressed);
+        assert!(state.button(GamepadButton::East).pressed);
+
+        let previous = state;
+        gamepad.handle_touch_event(&TouchEvent::End(touch(1, (300.0, 110.0))));
+        let events = gamepad::diff(Some(&previous), Some(&gamepad.state()));
+        assert_eq!(events, vec![gamepad::GamepadEvent::ButtonUp(index, GamepadButton::East.standard_index(), 0.0)]);
+
+        drop(gamepad);
+        assert!(gamepad::virtual_gamepads().iter().all(|state| state.index != index));
+    }
+
+    #[test]
+    fn several_gamepads() {
+        let menu = VirtualGamepad::new(VirtualJoystick::new((100.0, 100.0), 50.0));
+        let mut game = VirtualGamepad::new(VirtualJoystick::new((100.0, 100.0), 50.0));
+        assert_ne!(menu.get_index(), game.get_index());
+
+        // the setters are published immediately
+        game.add_button(GamepadButton::South, VirtualButton::new((300.0, 100.0), 20.0));
+        game.handle_touch_event(&TouchEvent::Start(touch(0, (300.0, 100.0))));
+        game.set_button_center(GamepadButton::South, (500.0, 100.0));
+        assert_eq!(game.get_buttons()[0].1.center, (500.0, 100.0));
+        game.remove_button(GamepadButton::South);
+        let published = |index| gamepad::virtual_gamepads().into_iter().find(|state| state.index == index);
+        assert!(!published(game.get_index()).unwrap().button(GamepadButton::South).pressed);
+
+        // dropping a gamepad does not unplug the other one
+        let game_index = game.get_index();
+        drop(menu);
+        assert!(published(game_index).is_some());
+        drop(game);
+        assert!(published(game_index).is_none());
+    }
+}
@@ -49,6 +49,7 @@
 
 pub mod graphics;
 pub mod inputs;
+pub mod audio;
 /// You will need this module for various things.
 #[macro_use]
 pub mod system;
@@ -0,0 +1,193 @@
+//! Measure time with a high resolution.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+use web_sys::window;
+
+/// Return the current time in milliseconds, with a sub-millisecond precision.
+/// This is [performance.now()](https://developer.mozilla.org/en-US/docs/Web/API/Performance/now).
+pub fn now() -> f64 {
+    window().unwrap().performance().unwrap().now()
+}
+
+/// A clock measuring the time elapsed since its creation or since its last restart, like the Clock of SFML.
+///
+/// # Example
+///
+/// ```no_run
+/// use wasm_game_lib::system::Clock;
+///
+/// let mut clock = Clock::new();
+/// // do something
+/// let elapsed = clock.restart();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Clock {
+    start: f64,
+}
+
+impl Clock {
+    /// Create a clock and start it.
+    pub fn new() -> Clock {
+        Clock { start: now() }
+    }
+
+    /// Return the time elapsed since the clock was started.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((now() - self.start).max(0.0) / 1000.0)
+    }
+
+    /// Restart the clock and return the time elapsed since it was started.
+    pub fn restart(&mut self) -> Duration {
+        let now = now();
+        let elapsed = Duration::from_secs_f64((now - self.start).max(0.0) / 1000.0);
+        self.start = now;
+        elapsed
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Clock {
+        Clock::new()
+    }
+}
+
+/// Statistics about the last frames: FPS, average, min and max frame time and percentiles.
+/// Only the last frames are kept, so the statistics follow the changes of performance.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::system::FrameStats;
+/// use std::time::Duration;
+///
+/// let mut stats = FrameStats::new(120);
+/// for timestamp in [0.0, 16.0, 32.0, 48.0, 100.0].iter() {
+///     // in a game, use the timestamp given by next_frame()
+///     stats.add_timestamp(*timestamp);
+/// }
+///
+/// assert_eq!(stats.get_max(), Duration::from_millis(52));
+/// assert_eq!(stats.get_fps(), 40.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    frame_times: VecDeque<Duration>,
+    capacity: usize,
+    last_timestamp: Option<f64>,
+}
+
+impl FrameStats {
+    /// Create statistics about the last `capacity` frames.
+    pub fn new(capacity: usize) -> FrameStats {
+        FrameStats {
+            frame_times: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            last_timestamp: None,
+        }
+    }
+
+    /// Add the duration of a frame.
+    pub fn add_frame_time(&mut self, frame_time: Duration) {
+        if self.frame_times.len() >= self.capacity {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    /// Add the time of a frame in milliseconds, like the value returned by next_frame() or now().
+    /// The first timestamp does not add any frame.
+    pub fn add_timestamp(&mut self, timestamp: f64) {
+        if let Some(last_timestamp) = self.last_timestamp {
+            self.add_frame_time(Duration::from_secs_f64((timestamp - last_timestamp).max(0.0) / 1000.0));
+        }
+        self.last_timestamp = Some(timestamp);
+    }
+
+    /// Forget every frame.
+    pub fn clear(&mut self) {
+        self.frame_times.clear();
+        self.last_timestamp = None;
+    }
+
+    /// Return the number of frames in the statistics.
+    pub fn get_frame_count(&self) -> usize {
+        self.frame_times.len()
+    }
+
+    /// Return the average number of frames per second, or 0.0 if there is no frame.
+    pub fn get_fps(&self) -> f64 {
+        let average = self.get_average().as_secs_f64();
+        if average > 0.0 {
+            1.0 / average
+        } else {
+            0.0
+        }
+    }
+
+    /// Return the average frame time.
+    pub fn get_average(&self) -> Duration {
+        if self.frame_times.is_empty() {
+            return Duration::from_secs(0);
+        }
+        self.frame_times.iter().sum::<Duration>() / self.frame_times.len() as u32
+    }
+
+    /// Return the shortest frame time.
+    pub fn get_min(&self) -> Duration {
+        self.frame_times.iter().min().copied().unwrap_or_default()
+    }
+
+    /// Return the longest frame time.
+    pub fn get_max(&self) -> Duration {
+        self.frame_times.iter().max().copied().unwrap_or_default()
+    }
+
+    /// Return the frame time under which `percentile` percents of the frames are.
+    /// For example, `get_percentile(99.0)` ignores the 1% slowest frames.
+    pub fn get_percentile(&self, percentile: f64) -> Duration {
+        if self.frame_times.is_empty() {
+            return Duration::from_secs(0);
+        }
+        let mut frame_times: Vec<Duration> = self.frame_times.iter().copied().collect();
+        frame_times.sort();
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * frame_times.len() as f64).ceil() as usize;
+        frame_times[rank.clamp(1, frame_times.len()) - 1]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn frame_stats() {
+        let mut stats = FrameStats::new(4);
+        assert_eq!(stats.get_fps(), 0.0);
+        assert_eq!(stats.get_percentile(50.0), Duration::from_secs(0));
+
+        for frame_time in [100, 10, 20, 30, 40].iter() {
+            stats.add_frame_time(Duration::from_millis(*frame_time));
+        }
+        // the first frame has been forgotten
+        assert_eq!(stats.get_frame_count(), 4);
+        assert_eq!(stats.get_average(), Duration::from_millis(25));
+        assert_eq!(stats.get_fps(), 40.0);
+        assert_eq!(stats.get_min(), Duration::from_millis(10));
+        assert_eq!(stats.get_max(), Duration::from_millis(40));
+        assert_eq!(stats.get_percentile(0.0), Duration::from_millis(10));
+        assert_eq!(stats.get_percentile(50.0), Duration::from_millis(20));
+        assert_eq!(stats.get_percentile(75.0), Duration::from_millis(30));
+        assert_eq!(stats.get_percentile(99.0), Duration::from_millis(40));
+    }
+
+    #[test]
+    fn timestamps() {
+        let mut stats = FrameStats::new(10);
+        stats.add_timestamp(5000.0);
+        assert_eq!(stats.get_frame_count(), 0);
+        stats.add_timestamp(5016.5);
+        stats.add_timestamp(5033.0);
+        assert_eq!(stats.get_average(), Duration::from_micros(16500));
+    }
+}
@@ -0,0 +1,163 @@
+//! A game loop driven by the refresh rate of the display.
+
+use std::time::Duration;
+use js_sys::Promise;
+use web_sys::window;
+use wasm_bindgen_futures::JsFuture;
+
+/// Wait until the browser is ready to display a new frame and return the current time in milliseconds.
+/// The browser calls it at the refresh rate of the display, and stops calling it while the tab is hidden.
+/// This is based on [requestAnimationFrame](https://developer.mozilla.org/en-US/docs/Web/API/window/requestAnimationFrame).
+pub async fn next_frame() -> f64 {
+    let promise = Promise::new(&mut |yes, _| {
+        window().unwrap().request_animation_frame(&yes).unwrap();
+    });
+    let js_fut = JsFuture::from(promise);
+    js_fut.await.unwrap().as_f64().unwrap_or_default()
+}
+
+/// A game loop updating the game at a fixed rate and rendering it at the refresh rate of the display.
+///
+/// Updating the game with a fixed timestep makes physics stable and deterministic.
+/// The time between two frames is accumulated, and [next_frame()](#method.next_frame) returns the number of updates to run to consume it.
+/// The remaining time is given by [get_alpha()](#method.get_alpha), to interpolate the rendering between the last two states of the game.
+///
+/// The time between two frames is clamped to the [max frame time](#method.set_max_frame_time),
+/// so that the game does not try to catch up after the tab was hidden or after a lag.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::system::GameLoop;
+/// use std::time::Duration;
+///
+/// # async fn test() {
+/// let mut game_loop = GameLoop::new(Duration::from_millis(10)); // 100 updates per second
+///
+/// loop {
+///     let updates = game_loop.next_frame().await;
+///     for _ in 0..updates {
+///         // update the game, game_loop.get_timestep() is the duration of an update
+///     }
+///     // render the game, interpolating with game_loop.get_alpha()
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct GameLoop {
+    timestep: Duration,
+    max_frame_time: Duration,
+    accumulator: Duration,
+    last_timestamp: Option<f64>,
+    delta: Duration,
+    elapsed: Duration,
+}
+
+impl GameLoop {
+    /// Create a game loop updating the game at a fixed timestep.
+    /// The max frame time is 250ms.
+    pub fn new(timestep: Duration) -> GameLoop {
+        GameLoop {
+            timestep,
+            max_frame_time: Duration::from_millis(250),
+            accumulator: Duration::from_secs(0),
+            last_timestamp: None,
+            delta: Duration::from_secs(0),
+            elapsed: Duration::from_secs(0),
+        }
+    }
+
+    /// Wait for the next frame of the display and return the number of fixed updates to run before rendering.
+    pub async fn next_frame(&mut self) -> u32 {
+        let timestamp = next_frame().await;
+        self.advance(timestamp)
+    }
+
+    /// Advance the loop to a time in milliseconds and return the number of fixed updates to run before rendering.
+    /// [next_frame()](#method.next_frame) calls this with the time of the frame, but you can use it with your own clock.
+    /// The first call only starts the loop and returns 0.
+    pub fn advance(&mut self, timestamp: f64) -> u32 {
+        let frame_time = match self.last_timestamp {
+            Some(last_timestamp) => Duration::from_secs_f64((timestamp - last_timestamp).max(0.0) / 1000.0),
+            None => Duration::from_secs(0),
+        };
+        self.last_timestamp = Some(timestamp);
+
+        self.delta = frame_time.min(self.max_frame_time);
+        self.elapsed += self.delta;
+        self.accumulator += self.delta;
+
+        let mut updates = 0;
+        if self.timestep > Duration::from_secs(0) {
+            while self.accumulator >= self.timestep {
+                self.accumulator -= self.timestep;
+                updates += 1;
+            }
+        }
+        updates
+    }
+
+    /// Return the duration of a fixed update.
+    pub fn get_timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    /// Set the duration of a fixed update.
+    pub fn set_timestep(&mut self, timestep: Duration) {
+        self.timestep = timestep;
+    }
+
+    /// Return the maximum time between two frames.
+    pub fn get_max_frame_time(&self) -> Duration {
+        self.max_frame_time
+    }
+
+    /// Set the maximum time between two frames.
+    /// A longer time is clamped so that the game slows down instead of running a lot of updates at once.
+    pub fn set_max_frame_time(&mut self, max_frame_time: Duration) {
+        self.max_frame_time = max_frame_time;
+    }
+
+    /// Return the time elapsed since the last frame (clamped to the max frame time).
+    pub fn get_delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Return the time elapsed since the first frame, without the time lost by clamping.
+    pub fn get_elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Return how far the game is between the last fixed update and the next one, between 0.0 and 1.0.
+    /// Use it to interpolate the rendering between the last two states of the game.
+    pub fn get_alpha(&self) -> f64 {
+        if self.timestep > Duration::from_secs(0) {
+            self.accumulator.as_secs_f64() / self.timestep.as_secs_f64()
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fixed_timestep() {
+        let mut game_loop = GameLoop::new(Duration::from_millis(10));
+        assert_eq!(game_loop.advance(1000.0), 0);
+
+        assert_eq!(game_loop.advance(1016.0), 1);
+        assert!((game_loop.get_alpha() - 0.6).abs() < 1e-6);
+        assert_eq!(game_loop.get_delta(), Duration::from_millis(16));
+
+        assert_eq!(game_loop.advance(1020.0), 1);
+        assert!(game_loop.get_alpha() < 1e-6);
+
+        // the tab was hidden for 10 seconds
+        assert_eq!(game_loop.advance(11020.0), 25);
+        assert_eq!(game_loop.get_delta(), Duration::from_millis(250));
+        assert_eq!(game_loop.get_elapsed(), Duration::from_millis(270));
+    }
+}
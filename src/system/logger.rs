@@ -0,0 +1,220 @@
+//! A logger for the [log](https://docs.rs/log) crate.
+//!
+//! Once the [Logger](struct.Logger.html) is initialized, the `debug!`, `info!`, `warn!` and `error!` macros of the log crate
+//! print to the browser console, including the logs of your dependencies.
+//! Logs can also be displayed in the game with a [LogOverlay](struct.LogOverlay.html).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use wasm_game_lib::system::logger::{Logger, LogOverlay};
+//! use log::LevelFilter;
+//!
+//! let overlay = LogOverlay::new(10);
+//! let mut logger = Logger::new(LevelFilter::Info);
+//! logger.set_module_level("my_game::physics", LevelFilter::Debug);
+//! logger.set_module_level("noisy_dependency", LevelFilter::Off);
+//! logger.set_overlay(overlay.clone());
+//! logger.init().unwrap();
+//!
+//! log::info!("game started");
+//! // in the game loop: canvas.draw(&overlay);
+//! ```
+
+use crate::graphics::canvas::Canvas;
+use crate::graphics::color::Color;
+use crate::graphics::drawable::Drawable;
+use ::log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use wasm_bindgen::JsValue;
+
+/// A logger printing to the browser console.
+/// Levels are mapped to `console.debug()` (trace and debug), `console.info()`, `console.warn()` and `console.error()`.
+#[derive(Debug, Clone)]
+pub struct Logger {
+    level: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+    overlay: Option<LogOverlay>,
+}
+
+impl Logger {
+    /// Create a logger accepting the records up to a level.
+    pub fn new(level: LevelFilter) -> Logger {
+        Logger {
+            level,
+            modules: Vec::new(),
+            overlay: None,
+        }
+    }
+
+    /// Set the default level, used for the modules which have no specific level.
+    pub fn set_level(&mut self, level: LevelFilter) {
+        self.level = level;
+    }
+
+    /// Set the level of a module and its submodules (`my_game::physics` also applies to `my_game::physics::collisions`).
+    /// The most specific module wins.
+    pub fn set_module_level(&mut self, module: &str, level: LevelFilter) {
+        self.modules.retain(|(name, _)| name != module);
+        self.modules.push((module.to_string(), level));
+    }
+
+    /// Return the level applying to a module.
+    pub fn get_level(&self, module: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .filter(|(name, _)| {
+                module.starts_with(name.as_str())
+                    && (module.len() == name.len() || module[name.len()..].starts_with("::"))
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level)
+    }
+
+    /// Also display the logs in a [LogOverlay](struct.LogOverlay.html).
+    pub fn set_overlay(&mut self, overlay: LogOverlay) {
+        self.overlay = Some(overlay);
+    }
+
+    /// Install the logger. Fails if a logger is already installed.
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let max_level = self.modules.iter().map(|(_, level)| *level).fold(self.level, Ord::max);
+        ::log::set_boxed_logger(Box::new(self))?;
+        ::log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.get_level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let message = format!("[{}] {}", record.target(), record.args());
+        let js_message = JsValue::from_str(&message);
+        match record.level() {
+            Level::Error => web_sys::console::error_1(&js_message),
+            Level::Warn => web_sys::console::warn_1(&js_message),
+            Level::Info => web_sys::console::info_1(&js_message),
+            Level::Debug | Level::Trace => web_sys::console::debug_1(&js_message),
+        }
+        if let Some(overlay) = &self.overlay {
+            overlay.push(record.level(), message);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// The last logs, drawable on a [Canvas](../../graphics/canvas/struct.Canvas.html).
+/// Clones share the same logs, so a clone can be given to the [Logger](struct.Logger.html).
+#[derive(Debug, Clone)]
+pub struct LogOverlay {
+    lines: Arc<Mutex<VecDeque<(Level, String)>>>,
+    capacity: usize,
+    /// The coords of the top left corner of the overlay in px.
+    pub coords: (f64, f64),
+}
+
+impl LogOverlay {
+    /// Create an overlay keeping the last `capacity` logs.
+    pub fn new(capacity: usize) -> LogOverlay {
+        LogOverlay {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+            coords: (0.0, 0.0),
+        }
+    }
+
+    /// Add a line, removing the oldest one if the overlay is full.
+    pub fn push(&self, level: Level, line: String) {
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() >= self.capacity {
+            lines.pop_front();
+        }
+        if self.capacity > 0 {
+            lines.push_back((level, line));
+        }
+    }
+
+    /// Return the displayed lines, from the oldest to the newest.
+    pub fn get_lines(&self) -> Vec<(Level, String)> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Remove all the lines.
+    pub fn clear(&self) {
+        self.lines.lock().unwrap().clear();
+    }
+}
+
+impl Drawable for LogOverlay {
+    fn draw_on_canvas(&self, canvas: &mut Canvas) {
+        const LINE_HEIGHT: f64 = 16.0;
+        let lines = self.get_lines();
+        if lines.is_empty() {
+            return;
+        }
+
+        let (x, y) = self.coords;
+        canvas.fill_rect((x, y), (f64::from(canvas.get_width()) - x, LINE_HEIGHT * lines.len() as f64 + 4.0), Color::new_with_alpha(0, 0, 0, 160));
+        let context = canvas.get_2d_canvas_rendering_context();
+        context.set_font("14px monospace");
+        context.set_text_baseline("top");
+        for (index, (level, line)) in lines.iter().enumerate() {
+            let color = match level {
+                Level::Error => Color::new(255, 80, 80),
+                Level::Warn => Color::new(255, 200, 0),
+                Level::Info => Color::white(),
+                Level::Debug | Level::Trace => Color::grey(),
+            };
+            context.set_fill_style_str(&color.to_string());
+            context.fill_text(line, x + 4.0, y + 2.0 + LINE_HEIGHT * index as f64).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn filtering() {
+        let mut logger = Logger::new(LevelFilter::Info);
+        logger.set_module_level("game::physics", LevelFilter::Trace);
+        logger.set_module_level("game::physics::broadphase", LevelFilter::Warn);
+        logger.set_module_level("dependency", LevelFilter::Off);
+
+        assert_eq!(logger.get_level("game"), LevelFilter::Info);
+        assert_eq!(logger.get_level("game::physics"), LevelFilter::Trace);
+        assert_eq!(logger.get_level("game::physics::solver"), LevelFilter::Trace);
+        assert_eq!(logger.get_level("game::physics::broadphase::grid"), LevelFilter::Warn);
+        assert_eq!(logger.get_level("game::physicsx"), LevelFilter::Info);
+        assert_eq!(logger.get_level("dependency::module"), LevelFilter::Off);
+
+        let metadata = |level, target| Metadata::builder().level(level).target(target).build();
+        assert!(logger.enabled(&metadata(Level::Info, "game")));
+        assert!(!logger.enabled(&metadata(Level::Debug, "game")));
+        assert!(logger.enabled(&metadata(Level::Debug, "game::physics")));
+        assert!(!logger.enabled(&metadata(Level::Error, "dependency")));
+    }
+
+    #[test]
+    fn overlay() {
+        let overlay = LogOverlay::new(2);
+        let overlay2 = overlay.clone();
+        overlay.push(Level::Info, "a".to_string());
+        overlay2.push(Level::Warn, "b".to_string());
+        overlay.push(Level::Error, "c".to_string());
+        assert_eq!(overlay2.get_lines(), vec![(Level::Warn, "b".to_string()), (Level::Error, "c".to_string())]);
+        overlay.clear();
+        assert!(overlay2.get_lines().is_empty());
+    }
+}
@@ -4,9 +4,16 @@ use web_sys::{window};
 use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen::prelude::*;
 
+pub mod game_loop;
+pub mod clock;
+pub mod timers;
+pub mod logger;
+pub use game_loop::{next_frame, GameLoop};
+pub use clock::{Clock, FrameStats};
+pub use logger::{Logger, LogOverlay};
+
 
 /// This is the wasm version of the sleep function.
-/// For now it is the only way to sleep.
 /// The precision of this function is 1ms.
 pub async fn sleep(duration: Duration) {
     let promise = Promise::new(&mut |yes, _| {
@@ -28,6 +35,7 @@ extern "C" {
 #[macro_export]
 /// A println-like macro. 
 /// **Warning**: This is very slow.
+/// The [Logger](system/logger/struct.Logger.html) is a better way to log.
 macro_rules! log {
     ($($t:tt)*) => ($crate::system::log(&format_args!($($t)*).to_string()))
 }
@@ -35,6 +43,7 @@ macro_rules! log {
 #[macro_export]
 /// A eprintln-like macro.
 /// **Warning**: This is **extremely** slow.
+/// The [Logger](system/logger/struct.Logger.html) is a better way to log.
 macro_rules! elog {
     ($($t:tt)*) => ($crate::system::error(&format_args!($($t)*).to_string()))
 }
\ No newline at end of file
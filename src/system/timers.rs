@@ -0,0 +1,310 @@
+//! Timers measured in game time.
+//!
+//! Unlike [sleep()](../fn.sleep.html), which uses the real time, a [Scheduler](struct.Scheduler.html) only advances when the game tells it to.
+//! Its timers and futures stop while the game is paused.
+
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+struct Timer {
+    due: Duration,
+    interval: Option<Duration>,
+    callback: Box<dyn FnMut()>,
+    active: Rc<Cell<bool>>,
+}
+
+#[derive(Default)]
+struct State {
+    time: Duration,
+    frame: u64,
+    paused: bool,
+    timers: Vec<Timer>,
+    wakers: Vec<Waker>,
+}
+
+/// A handle to a timer created by a [Scheduler](struct.Scheduler.html).
+#[derive(Debug, Clone)]
+pub struct TimerHandle {
+    active: Rc<Cell<bool>>,
+}
+
+impl TimerHandle {
+    /// Cancel the timer. Its callback will not be called anymore.
+    pub fn cancel(&self) {
+        self.active.set(false);
+    }
+
+    /// Return false if the timer has been cancelled or if it was a one-shot timer which already fired.
+    pub fn is_active(&self) -> bool {
+        self.active.get()
+    }
+}
+
+/// A scheduler running timers and futures in game time.
+/// Call [update()](#method.update) once per frame with the duration of the frame.
+/// Clones share the same timers, so a clone can be moved into an async task.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_game_lib::system::timers::Scheduler;
+/// use std::time::Duration;
+/// use std::rc::Rc;
+/// use std::cell::Cell;
+///
+/// let scheduler = Scheduler::new();
+/// let spawned = Rc::new(Cell::new(0));
+/// let spawned2 = Rc::clone(&spawned);
+/// let spawner = scheduler.every(Duration::from_secs(2), move || spawned2.set(spawned2.get() + 1));
+///
+/// // in the game loop
+/// scheduler.update(Duration::from_millis(1500));
+/// scheduler.pause(); // the player opened the menu
+/// scheduler.update(Duration::from_secs(60));
+/// scheduler.resume();
+/// scheduler.update(Duration::from_millis(1500));
+/// assert_eq!(spawned.get(), 1);
+///
+/// spawner.cancel();
+/// scheduler.update(Duration::from_secs(10));
+/// assert_eq!(spawned.get(), 1);
+/// ```
+///
+/// Cutscenes can be scripted with async code:
+///
+/// ```rust
+/// use wasm_game_lib::system::timers::Scheduler;
+/// use std::time::Duration;
+///
+/// async fn cutscene(scheduler: Scheduler) {
+///     // show a dialog
+///     scheduler.wait_game_time(Duration::from_secs(3)).await;
+///     // move the camera
+///     scheduler.wait_frames(60).await;
+///     // give the control back to the player
+/// }
+/// // spawn it with wasm_bindgen_futures::spawn_local(cutscene(scheduler.clone()))
+/// ```
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    state: Rc<RefCell<State>>,
+}
+
+impl Scheduler {
+    /// Create a scheduler with a game time of 0.
+    pub fn new() -> Scheduler {
+        Scheduler::default()
+    }
+
+    fn add_timer(&self, delay: Duration, interval: Option<Duration>, callback: Box<dyn FnMut()>) -> TimerHandle {
+        let active = Rc::new(Cell::new(true));
+        let mut state = self.state.borrow_mut();
+        let due = state.time + delay;
+        state.timers.push(Timer {
+            due,
+            interval,
+            callback,
+            active: Rc::clone(&active),
+        });
+        TimerHandle { active }
+    }
+
+    /// Call a function once, after a delay in game time.
+    pub fn after(&self, delay: Duration, callback: impl FnMut() + 'static) -> TimerHandle {
+        self.add_timer(delay, None, Box::new(callback))
+    }
+
+    /// Call a function repeatedly, at an interval in game time.
+    /// The first call happens after one interval.
+    pub fn every(&self, interval: Duration, callback: impl FnMut() + 'static) -> TimerHandle {
+        self.add_timer(interval, Some(interval), Box::new(callback))
+    }
+
+    /// Advance the game time, call the functions of the timers which are due and wake the futures which are ready.
+    /// Nothing happens while the scheduler is paused.
+    pub fn update(&self, delta: Duration) {
+        let (time, mut due, wakers) = {
+            let mut state = self.state.borrow_mut();
+            if state.paused {
+                return;
+            }
+            state.time += delta;
+            state.frame += 1;
+            let time = state.time;
+            let (due, pending): (Vec<Timer>, Vec<Timer>) = std::mem::take(&mut state.timers)
+                .into_iter()
+                .filter(|timer| timer.active.get())
+                .partition(|timer| timer.due <= time);
+            state.timers = pending;
+            (time, due, std::mem::take(&mut state.wakers))
+        };
+
+        // callbacks are called without borrowing the state, so that they can use the scheduler
+        loop {
+            let next = due
+                .iter()
+                .enumerate()
+                .filter(|(_, timer)| timer.active.get() && timer.due <= time)
+                .min_by_key(|(_, timer)| timer.due)
+                .map(|(index, _)| index);
+            let timer = match next {
+                Some(index) => &mut due[index],
+                None => break,
+            };
+            (timer.callback)();
+            match timer.interval {
+                Some(interval) if interval > Duration::from_secs(0) => timer.due += interval,
+                Some(_) => timer.due = time + Duration::from_nanos(1),
+                None => timer.active.set(false),
+            }
+        }
+        self.state.borrow_mut().timers.extend(due.into_iter().filter(|timer| timer.active.get()));
+
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Stop the game time. Use it when the game is paused.
+    pub fn pause(&self) {
+        self.state.borrow_mut().paused = true;
+    }
+
+    /// Restart the game time after a [pause](#method.pause).
+    pub fn resume(&self) {
+        self.state.borrow_mut().paused = false;
+    }
+
+    /// Return true if the scheduler is paused.
+    pub fn is_paused(&self) -> bool {
+        self.state.borrow().paused
+    }
+
+    /// Return the game time, which is the sum of the durations given to [update()](#method.update) while the scheduler was not paused.
+    pub fn get_time(&self) -> Duration {
+        self.state.borrow().time
+    }
+
+    /// Return the number of updates while the scheduler was not paused.
+    pub fn get_frame(&self) -> u64 {
+        self.state.borrow().frame
+    }
+
+    /// Return a future which completes after a number of updates.
+    pub fn wait_frames(&self, frames: u64) -> Wait {
+        Wait {
+            state: Rc::clone(&self.state),
+            target: Target::Frame(self.get_frame() + frames),
+        }
+    }
+
+    /// Return a future which completes after a duration in game time.
+    pub fn wait_game_time(&self, duration: Duration) -> Wait {
+        Wait {
+            state: Rc::clone(&self.state),
+            target: Target::Time(self.get_time() + duration),
+        }
+    }
+}
+
+enum Target {
+    Frame(u64),
+    Time(Duration),
+}
+
+/// A future returned by [wait_frames()](struct.Scheduler.html#method.wait_frames) and [wait_game_time()](struct.Scheduler.html#method.wait_game_time).
+pub struct Wait {
+    state: Rc<RefCell<State>>,
+    target: Target,
+}
+
+impl Future for Wait {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let mut state = self.state.borrow_mut();
+        let ready = match self.target {
+            Target::Frame(frame) => state.frame >= frame,
+            Target::Time(time) => state.time >= time,
+        };
+        if ready {
+            Poll::Ready(())
+        } else {
+            state.wakers.push(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::LocalPool;
+    use futures::task::LocalSpawnExt;
+
+    #[test]
+    fn timers() {
+        let scheduler = Scheduler::new();
+        let calls = Rc::new(RefCell::new(Vec::new()));
+
+        let calls2 = Rc::clone(&calls);
+        let once = scheduler.after(Duration::from_millis(50), move || calls2.borrow_mut().push("once"));
+        let calls2 = Rc::clone(&calls);
+        scheduler.every(Duration::from_millis(20), move || calls2.borrow_mut().push("every"));
+
+        scheduler.update(Duration::from_millis(30));
+        assert_eq!(*calls.borrow(), vec!["every"]);
+        scheduler.update(Duration::from_millis(30));
+        assert_eq!(*calls.borrow(), vec!["every", "every", "once", "every"]);
+        assert!(!once.is_active());
+
+        // timers can be created by callbacks
+        let scheduler2 = scheduler.clone();
+        let calls2 = Rc::clone(&calls);
+        scheduler.after(Duration::from_millis(0), move || {
+            let calls3 = Rc::clone(&calls2);
+            scheduler2.after(Duration::from_millis(0), move || calls3.borrow_mut().push("nested"));
+        });
+        scheduler.update(Duration::from_millis(5));
+        scheduler.update(Duration::from_millis(5));
+        assert_eq!(calls.borrow().last(), Some(&"nested"));
+    }
+
+    #[test]
+    fn futures() {
+        let scheduler = Scheduler::new();
+        let mut pool = LocalPool::new();
+        let progress = Rc::new(Cell::new(0));
+
+        let scheduler2 = scheduler.clone();
+        let progress2 = Rc::clone(&progress);
+        pool.spawner().spawn_local(async move {
+            scheduler2.wait_frames(2).await;
+            progress2.set(1);
+            scheduler2.wait_game_time(Duration::from_secs(1)).await;
+            progress2.set(2);
+        }).unwrap();
+
+        pool.run_until_stalled();
+        scheduler.update(Duration::from_millis(100));
+        pool.run_until_stalled();
+        assert_eq!(progress.get(), 0);
+        scheduler.update(Duration::from_millis(100));
+        pool.run_until_stalled();
+        assert_eq!(progress.get(), 1);
+
+        scheduler.pause();
+        scheduler.update(Duration::from_secs(5));
+        pool.run_until_stalled();
+        assert_eq!(progress.get(), 1);
+
+        scheduler.resume();
+        scheduler.update(Duration::from_secs(1));
+        pool.run_until_stalled();
+        assert_eq!(progress.get(), 2);
+    }
+}